@@ -0,0 +1,398 @@
+//! Payload builders for the entry functions of the `verifier_api` package.
+
+use crate::kzg::KzgVariant;
+use crate::payload::AccountAddress;
+use crate::transaction::{derive_object_address, derive_resource_account_address};
+use crate::types::{EntryFunctionArgumentsJSON, MoveArg};
+use crate::{create_params_args, publish_circuit_args, verify_batch_proof_args, verify_proof_args};
+use anyhow::Result;
+use halo2_proofs::halo2curves::bn256::{Bn256, Fr, G1Affine};
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use shape_generator::{srs_fingerprint, CircuitInfo};
+
+/// the module and function names, as in `verifier_api.move` and `param_store.move`.
+pub const VERIFIER_MODULE: &str = "verifier_api";
+pub const PUBLISH_CIRCUIT: &str = "publish_circuit";
+pub const PUBLISH_CIRCUIT_TO_OBJECT: &str = "publish_circuit_to_object";
+pub const PUBLISH_CIRCUIT_TO_RESOURCE_ACCOUNT: &str = "publish_circuit_to_resource_account";
+pub const PUBLISH_CIRCUIT_WITH_SRS: &str = "publish_circuit_with_srs";
+pub const PUBLISH_CIRCUIT_WITH_SRS_TO_OBJECT: &str = "publish_circuit_with_srs_to_object";
+pub const PUBLISH_CIRCUIT_WITH_SRS_TO_RESOURCE_ACCOUNT: &str =
+    "publish_circuit_with_srs_to_resource_account";
+pub const VERIFY_PROOF: &str = "verify_proof";
+pub const VERIFY_PROOF_GWC: &str = "verify_proof_gwc";
+pub const VERIFY_PROOF_SHPLONK: &str = "verify_proof_shplonk";
+pub const MOCK_VERIFY_PROOF: &str = "mock_verify_proof";
+pub const VERIFY_BATCH_PROOF: &str = "verify_batch_proof";
+pub const MOCK_VERIFY_BATCH_PROOF: &str = "mock_verify_batch_proof";
+pub const PARAM_STORE_MODULE: &str = "param_store";
+pub const CREATE_PARAMS: &str = "create";
+pub const CREATE_PARAMS_TO_OBJECT: &str = "create_to_object";
+pub const CREATE_PARAMS_TO_RESOURCE_ACCOUNT: &str = "create_to_resource_account";
+
+const HEX: &str = "vector<u8>";
+
+/// the parameter types of an entry function of the package, the signer excluded.
+/// `function_id` is `<address>::<module>::<function>`, only the module and function are matched.
+/// Payloads are parsed against them, as the json of an empty nested vector
+/// does not tell how deep it is nested.
+pub fn entry_function_signature(function_id: &str) -> Option<Vec<&'static str>> {
+    let mut names = function_id.rsplit("::");
+    let (function, module) = (names.next()?, names.next()?);
+    match module {
+        VERIFIER_MODULE => verifier_signature(function),
+        PARAM_STORE_MODULE => param_store_signature(function),
+        _ => None,
+    }
+}
+
+fn verifier_signature(function: &str) -> Option<Vec<&'static str>> {
+    const SHAPE: &str = "vector<vector<u8>>";
+    const INSTANCES: &str = "vector<vector<vector<u8>>>";
+    const BATCH_INSTANCES: &str = "vector<vector<vector<vector<u8>>>>";
+    let signature = match function {
+        PUBLISH_CIRCUIT => vec![SHAPE; 11],
+        PUBLISH_CIRCUIT_TO_OBJECT | PUBLISH_CIRCUIT_TO_RESOURCE_ACCOUNT => {
+            [vec![HEX], vec![SHAPE; 11]].concat()
+        }
+        PUBLISH_CIRCUIT_WITH_SRS => [vec![SHAPE; 11], vec![HEX]].concat(),
+        PUBLISH_CIRCUIT_WITH_SRS_TO_OBJECT | PUBLISH_CIRCUIT_WITH_SRS_TO_RESOURCE_ACCOUNT => {
+            [vec![HEX], vec![SHAPE; 11], vec![HEX]].concat()
+        }
+        VERIFY_PROOF | MOCK_VERIFY_PROOF => vec!["address", "address", INSTANCES, HEX, "u8"],
+        VERIFY_PROOF_GWC | VERIFY_PROOF_SHPLONK => vec!["address", "address", INSTANCES, HEX],
+        VERIFY_BATCH_PROOF | MOCK_VERIFY_BATCH_PROOF => {
+            vec!["address", "address", BATCH_INSTANCES, HEX, "u8"]
+        }
+        _ => return None,
+    };
+    Some(signature)
+}
+
+fn param_store_signature(function: &str) -> Option<Vec<&'static str>> {
+    match function {
+        CREATE_PARAMS => Some(vec![HEX; 3]),
+        CREATE_PARAMS_TO_OBJECT | CREATE_PARAMS_TO_RESOURCE_ACCOUNT => Some(vec![HEX; 4]),
+        _ => None,
+    }
+}
+
+/// Where `publish_circuit` and `param_store::create` store their resource.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Deployment {
+    /// under the sender account.
+    Sender,
+    /// under a named object of the sender, created from the seed.
+    Object(Vec<u8>),
+    /// under a resource account of the sender, created from the seed.
+    ResourceAccount(Vec<u8>),
+}
+
+impl Deployment {
+    /// the address the resource ends up at when `deployer` sends the transaction.
+    /// it only depends on the deployer and the seed, so it can be computed before deploying.
+    pub fn address(&self, deployer: &AccountAddress) -> AccountAddress {
+        match self {
+            Deployment::Sender => *deployer,
+            Deployment::Object(seed) => derive_object_address(deployer, seed),
+            Deployment::ResourceAccount(seed) => derive_resource_account_address(deployer, seed),
+        }
+    }
+
+    /// the entry function to call, and the seed argument that goes before the others.
+    fn function<'a>(
+        &self,
+        sender: &'a str,
+        object: &'a str,
+        resource_account: &'a str,
+    ) -> (&'a str, Option<MoveArg>) {
+        match self {
+            Deployment::Sender => (sender, None),
+            Deployment::Object(seed) => (object, Some(MoveArg::Hex(seed.clone()))),
+            Deployment::ResourceAccount(seed) => {
+                (resource_account, Some(MoveArg::Hex(seed.clone())))
+            }
+        }
+    }
+}
+
+/// Builds the payloads of a deployed `verifier_api` package.
+///
+/// In mock mode every verify call goes to `mock_verify_proof`, which accepts any proof.
+/// It is meant for integration environments where the verifier is stubbed.
+///
+/// Modules with other function names are reached with `with_publish_circuit_function`
+/// and `with_verify_proof_function`, they take the same arguments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierApi {
+    address: String,
+    module: String,
+    mock: bool,
+    publish_circuit_function: String,
+    verify_proof_function: String,
+}
+
+impl VerifierApi {
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            module: VERIFIER_MODULE.to_string(),
+            mock: false,
+            publish_circuit_function: PUBLISH_CIRCUIT.to_string(),
+            verify_proof_function: VERIFY_PROOF.to_string(),
+        }
+    }
+
+    /// the name of the verifier module, `verifier_api` by default.
+    pub fn with_module(mut self, module: impl Into<String>) -> Self {
+        self.module = module.into();
+        self
+    }
+
+    pub fn with_mock(mut self, mock: bool) -> Self {
+        self.mock = mock;
+        self
+    }
+
+    /// the function `publish_circuit` calls for `Deployment::Sender`, `publish_circuit` by default.
+    pub fn with_publish_circuit_function(mut self, function: impl Into<String>) -> Self {
+        self.publish_circuit_function = function.into();
+        self
+    }
+
+    /// the function `verify_proof` calls out of mock mode, `verify_proof` by default.
+    pub fn with_verify_proof_function(mut self, function: impl Into<String>) -> Self {
+        self.verify_proof_function = function.into();
+        self
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    pub fn is_mock(&self) -> bool {
+        self.mock
+    }
+
+    pub fn function_id(&self, function: &str) -> String {
+        format!("{}::{}::{}", self.address, self.module, function)
+    }
+
+    /// the parameter types of a function this api builds payloads for,
+    /// under the module and function names it was configured with.
+    pub fn signature(&self, function_id: &str) -> Option<Vec<&'static str>> {
+        let mut names = function_id.rsplit("::");
+        let (function, module) = (names.next()?, names.next()?);
+        if module == PARAM_STORE_MODULE {
+            return param_store_signature(function);
+        }
+        if module != self.module {
+            return None;
+        }
+        if function == self.publish_circuit_function {
+            verifier_signature(PUBLISH_CIRCUIT)
+        } else if function == self.verify_proof_function {
+            verifier_signature(VERIFY_PROOF)
+        } else {
+            verifier_signature(function)
+        }
+    }
+
+    /// read back a payload this api built, its arguments typed by `signature`.
+    pub fn read_payload(&self, json: &str) -> Result<EntryFunctionArgumentsJSON> {
+        EntryFunctionArgumentsJSON::from_json_with(json, |function_id| self.signature(function_id))
+    }
+
+    fn payload(&self, function: &str, args: Vec<MoveArg>) -> EntryFunctionArgumentsJSON {
+        EntryFunctionArgumentsJSON::new(self.function_id(function), args)
+    }
+
+    /// `param_store::create`, which lives in the same package as the verifier module.
+    pub fn create_params(&self, params: &ParamsKZG<Bn256>) -> Result<EntryFunctionArgumentsJSON> {
+        self.create_params_to(params, &Deployment::Sender)
+    }
+
+    pub fn create_params_to(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        deployment: &Deployment,
+    ) -> Result<EntryFunctionArgumentsJSON> {
+        let (function, seed) = deployment.function(
+            CREATE_PARAMS,
+            CREATE_PARAMS_TO_OBJECT,
+            CREATE_PARAMS_TO_RESOURCE_ACCOUNT,
+        );
+        Ok(EntryFunctionArgumentsJSON::new(
+            format!("{}::{}::{}", self.address, PARAM_STORE_MODULE, function),
+            seed.into_iter()
+                .chain(create_params_args(params)?)
+                .collect(),
+        ))
+    }
+
+    pub fn publish_circuit(
+        &self,
+        protocol: &CircuitInfo<G1Affine>,
+    ) -> Result<EntryFunctionArgumentsJSON> {
+        self.publish_circuit_to(protocol, &Deployment::Sender)
+    }
+
+    pub fn publish_circuit_to(
+        &self,
+        protocol: &CircuitInfo<G1Affine>,
+        deployment: &Deployment,
+    ) -> Result<EntryFunctionArgumentsJSON> {
+        let (function, seed) = deployment.function(
+            &self.publish_circuit_function,
+            PUBLISH_CIRCUIT_TO_OBJECT,
+            PUBLISH_CIRCUIT_TO_RESOURCE_ACCOUNT,
+        );
+        Ok(self.payload(
+            function,
+            seed.into_iter()
+                .chain(publish_circuit_args(protocol)?)
+                .collect(),
+        ))
+    }
+
+    /// `publish_circuit_with_srs`, which binds the circuit to the fingerprint of `params`,
+    /// the ones stored at the param address the proofs will be verified with.
+    /// The shape is checked to be keyed with them first.
+    pub fn publish_circuit_with_srs(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        protocol: &CircuitInfo<G1Affine>,
+        deployment: &Deployment,
+    ) -> Result<EntryFunctionArgumentsJSON> {
+        protocol.check_srs_fingerprint(params)?;
+        let (function, seed) = deployment.function(
+            PUBLISH_CIRCUIT_WITH_SRS,
+            PUBLISH_CIRCUIT_WITH_SRS_TO_OBJECT,
+            PUBLISH_CIRCUIT_WITH_SRS_TO_RESOURCE_ACCOUNT,
+        );
+        Ok(self.payload(
+            function,
+            seed.into_iter()
+                .chain(publish_circuit_args(protocol)?)
+                .chain([MoveArg::Hex(srs_fingerprint(params).to_vec())])
+                .collect(),
+        ))
+    }
+
+    pub fn verify_proof(
+        &self,
+        param_address: String,
+        circuit_address: String,
+        instances: &[Vec<Fr>],
+        proof: Vec<u8>,
+        variant: KzgVariant,
+    ) -> EntryFunctionArgumentsJSON {
+        let function = if self.mock {
+            MOCK_VERIFY_PROOF
+        } else {
+            self.verify_proof_function.as_str()
+        };
+        self.payload(
+            function,
+            verify_proof_args(proof, variant, instances, param_address, circuit_address),
+        )
+    }
+
+    pub fn verify_proof_gwc(
+        &self,
+        param_address: String,
+        circuit_address: String,
+        instances: &[Vec<Fr>],
+        proof: Vec<u8>,
+    ) -> EntryFunctionArgumentsJSON {
+        self.verify_proof_with_variant(
+            VERIFY_PROOF_GWC,
+            param_address,
+            circuit_address,
+            instances,
+            proof,
+            KzgVariant::Gwc,
+        )
+    }
+
+    pub fn verify_proof_shplonk(
+        &self,
+        param_address: String,
+        circuit_address: String,
+        instances: &[Vec<Fr>],
+        proof: Vec<u8>,
+    ) -> EntryFunctionArgumentsJSON {
+        self.verify_proof_with_variant(
+            VERIFY_PROOF_SHPLONK,
+            param_address,
+            circuit_address,
+            instances,
+            proof,
+            KzgVariant::Shplonk,
+        )
+    }
+
+    /// `mock_verify_proof`, regardless of the mock mode.
+    pub fn mock_verify_proof(
+        &self,
+        param_address: String,
+        circuit_address: String,
+        instances: &[Vec<Fr>],
+        proof: Vec<u8>,
+        variant: KzgVariant,
+    ) -> EntryFunctionArgumentsJSON {
+        self.payload(
+            MOCK_VERIFY_PROOF,
+            verify_proof_args(proof, variant, instances, param_address, circuit_address),
+        )
+    }
+
+    /// `verify_batch_proof`, for a proof of several instances of the circuit.
+    pub fn verify_batch_proof(
+        &self,
+        param_address: String,
+        circuit_address: String,
+        instances: &[Vec<Vec<Fr>>],
+        proof: Vec<u8>,
+        variant: KzgVariant,
+    ) -> EntryFunctionArgumentsJSON {
+        let function = if self.mock {
+            MOCK_VERIFY_BATCH_PROOF
+        } else {
+            VERIFY_BATCH_PROOF
+        };
+        self.payload(
+            function,
+            verify_batch_proof_args(proof, variant, instances, param_address, circuit_address),
+        )
+    }
+
+    /// the gwc and shplonk shortcuts take no variant argument,
+    /// in mock mode they fall back to `mock_verify_proof` with the variant filled in.
+    fn verify_proof_with_variant(
+        &self,
+        function: &str,
+        param_address: String,
+        circuit_address: String,
+        instances: &[Vec<Fr>],
+        proof: Vec<u8>,
+        variant: KzgVariant,
+    ) -> EntryFunctionArgumentsJSON {
+        if self.mock {
+            return self.mock_verify_proof(
+                param_address,
+                circuit_address,
+                instances,
+                proof,
+                variant,
+            );
+        }
+        let mut args = verify_proof_args(proof, variant, instances, param_address, circuit_address);
+        args.pop();
+        self.payload(function, args)
+    }
+}
@@ -0,0 +1,181 @@
+//! A proof with what it takes to build its payload, or check it, on another machine.
+
+use crate::api::VerifierApi;
+use crate::kzg::KzgVariant;
+use crate::proving::verify;
+use crate::types::EntryFunctionArgumentsJSON;
+use anyhow::{anyhow, bail, ensure, Result};
+use halo2_proofs::halo2curves::bn256::{Bn256, Fr, G1Affine};
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::plonk::VerifyingKey;
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use serde::{Deserialize, Serialize};
+use shape_generator::CircuitInfo;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The transcript hash of a proof. `halo2_verifier.move` only reads keccak256 transcripts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TranscriptKind {
+    Keccak256,
+}
+
+impl fmt::Display for TranscriptKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptKind::Keccak256 => write!(f, "keccak256"),
+        }
+    }
+}
+
+impl FromStr for TranscriptKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "keccak256" => Ok(TranscriptKind::Keccak256),
+            _ => bail!("unknown transcript {}, expect keccak256", s),
+        }
+    }
+}
+
+/// A proof, its instances, and the circuit it was proved for, identified by the
+/// `CircuitInfo::fingerprint` of its shape.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofBundle {
+    pub proof: Vec<u8>,
+    pub instances: Vec<Vec<Fr>>,
+    pub kzg_variant: KzgVariant,
+    pub shape_fingerprint: [u8; 32],
+    pub transcript: TranscriptKind,
+}
+
+/// The file format of a `ProofBundle`, bytes in hex as in payloads.
+/// Instances are `0x` prefixed big endian hex, as field elements of witness files,
+/// while payloads carry them little endian.
+#[derive(Deserialize, Serialize)]
+struct ProofBundleJSON {
+    proof: String,
+    instances: Vec<Vec<String>>,
+    kzg_variant: String,
+    shape_fingerprint: String,
+    transcript: String,
+}
+
+impl ProofBundle {
+    /// bundle a keccak256 proof, `shape` is the circuit it was proved for.
+    pub fn new(
+        proof: Vec<u8>,
+        instances: Vec<Vec<Fr>>,
+        kzg_variant: KzgVariant,
+        shape: &CircuitInfo<G1Affine>,
+    ) -> Result<Self> {
+        Ok(ProofBundle {
+            proof,
+            instances,
+            kzg_variant,
+            shape_fingerprint: shape.fingerprint()?,
+            transcript: TranscriptKind::Keccak256,
+        })
+    }
+
+    /// the `verify_proof` payload of the bundle.
+    pub fn verify_payload(
+        &self,
+        api: &VerifierApi,
+        param_address: String,
+        circuit_address: String,
+    ) -> EntryFunctionArgumentsJSON {
+        api.verify_proof(
+            param_address,
+            circuit_address,
+            &self.instances,
+            self.proof.clone(),
+            self.kzg_variant,
+        )
+    }
+
+    /// check the proof off-chain, as `verify_proof` would on-chain with the circuit `shape`.
+    pub fn verify(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        vk: &VerifyingKey<G1Affine>,
+        shape: &CircuitInfo<G1Affine>,
+    ) -> Result<()> {
+        let fingerprint = shape.fingerprint()?;
+        ensure!(
+            fingerprint == self.shape_fingerprint,
+            "the proof is for the circuit 0x{}, not 0x{}",
+            hex::encode(self.shape_fingerprint),
+            hex::encode(fingerprint)
+        );
+        shape.check_srs_fingerprint(params)?;
+        verify(params, vk, &self.instances, &self.proof, self.kzg_variant)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        let json = ProofBundleJSON {
+            proof: format!("0x{}", hex::encode(&self.proof)),
+            instances: self
+                .instances
+                .iter()
+                .map(|column| {
+                    column
+                        .iter()
+                        .map(|f| {
+                            let mut repr = f.to_repr();
+                            repr.as_mut().reverse();
+                            format!("0x{}", hex::encode(repr))
+                        })
+                        .collect()
+                })
+                .collect(),
+            kzg_variant: self.kzg_variant.to_string(),
+            shape_fingerprint: format!("0x{}", hex::encode(self.shape_fingerprint)),
+            transcript: self.transcript.to_string(),
+        };
+        Ok(serde_json::to_string_pretty(&json)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let json: ProofBundleJSON = serde_json::from_str(json)?;
+        let instances = json
+            .instances
+            .iter()
+            .map(|column| column.iter().map(|f| decode_fr(f)).collect())
+            .collect::<Result<_>>()?;
+        let shape_fingerprint = decode_hex(&json.shape_fingerprint)?
+            .try_into()
+            .map_err(|_| anyhow!("the shape fingerprint is not 32 bytes"))?;
+        Ok(ProofBundle {
+            proof: decode_hex(&json.proof)?,
+            instances,
+            kzg_variant: json.kzg_variant.parse()?,
+            shape_fingerprint,
+            transcript: json.transcript.parse()?,
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        Ok(std::fs::write(path, self.to_json()?)?)
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>> {
+    Ok(hex::decode(s.trim_start_matches("0x"))?)
+}
+
+/// a field element in big endian.
+fn decode_fr(s: &str) -> Result<Fr> {
+    let mut repr = <Fr as PrimeField>::Repr::default();
+    let bytes = decode_hex(s)?;
+    ensure!(bytes.len() == 32, "instance {} is not 32 bytes", s);
+    repr.as_mut().copy_from_slice(&bytes);
+    repr.as_mut().reverse();
+    Option::from(Fr::from_repr(repr)).ok_or_else(|| anyhow!("instance {} is not canonical", s))
+}
@@ -0,0 +1,203 @@
+//! Proving and verifying keys kept on disk, so that keygen runs once per circuit and setup.
+
+use anyhow::Result;
+use halo2_proofs::halo2curves::bn256::{Bn256, Fr, G1Affine};
+use halo2_proofs::plonk::{
+    keygen_pk, keygen_vk, pk_read, vk_read, Circuit, ProvingKey, VerifyingKey,
+};
+use halo2_proofs::poly::commitment::Params;
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use halo2_proofs::SerdeFormat;
+use shape_generator::{generate_circuit_info_from_vk, preprocessing_fingerprint, srs_fingerprint};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// A directory of keys, by circuit name, k and srs fingerprint.
+///
+/// Next to the keys it stores the `CircuitInfo::fingerprint` of the shape they were generated for,
+/// and the `preprocessing_fingerprint` of the fixed column values and copy constraints.
+/// Keys read back are rebuilt with the gates of the current circuit, and only used if
+/// their shape still has that fingerprint, and the current circuit has the same
+/// fixed values and copies, which the shape does not show.
+pub struct KeyCache {
+    dir: PathBuf,
+    format: SerdeFormat,
+    regenerate: bool,
+}
+
+impl KeyCache {
+    /// keys are stored as `SerdeFormat::RawBytes` by default.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            format: SerdeFormat::RawBytes,
+            regenerate: false,
+        }
+    }
+
+    pub fn with_format(mut self, format: SerdeFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// ignore the cached keys, and overwrite them with new ones.
+    pub fn with_regenerate(mut self, regenerate: bool) -> Self {
+        self.regenerate = regenerate;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// `<dir>/<name>-k<k>-<srs fingerprint>`, the keys and fingerprints
+    /// are stored with the `pk`, `vk` and `shape` extensions.
+    pub fn key_path(&self, name: &str, params: &ParamsKZG<Bn256>) -> PathBuf {
+        self.dir.join(format!(
+            "{}-k{}-{}",
+            name,
+            params.k(),
+            hex::encode(srs_fingerprint(params))
+        ))
+    }
+
+    /// the proving key of `circuit`, read from the cache or generated and cached.
+    pub fn proving_key<C: Circuit<Fr>>(
+        &self,
+        name: &str,
+        params: &ParamsKZG<Bn256>,
+        circuit: &C,
+    ) -> Result<ProvingKey<G1Affine>> {
+        let path = self.key_path(name, params);
+        let preprocessing = preprocessing_fingerprint(params.k(), circuit)?;
+        if let Some(pk) = self.read_pk(&path, params, circuit, &preprocessing)? {
+            return Ok(pk);
+        }
+        let vk = keygen_vk(params, circuit)?;
+        let fingerprints = key_fingerprints(params, &vk, &preprocessing)?;
+        let pk = keygen_pk(params, vk, circuit)?;
+        std::fs::create_dir_all(&self.dir)?;
+        write_key(&path.with_extension("vk"), |w| {
+            pk.get_vk().write(w, self.format)
+        })?;
+        write_key(&path.with_extension("pk"), |w| pk.write(w, self.format))?;
+        std::fs::write(path.with_extension("shape"), fingerprints)?;
+        Ok(pk)
+    }
+
+    /// the verifying key of `circuit`, read from the cache or generated and cached.
+    pub fn verifying_key<C: Circuit<Fr>>(
+        &self,
+        name: &str,
+        params: &ParamsKZG<Bn256>,
+        circuit: &C,
+    ) -> Result<VerifyingKey<G1Affine>> {
+        let path = self.key_path(name, params);
+        let preprocessing = preprocessing_fingerprint(params.k(), circuit)?;
+        if let Some(vk) = self.read_vk(&path, params, circuit, &preprocessing)? {
+            return Ok(vk);
+        }
+        let vk = keygen_vk(params, circuit)?;
+        let fingerprints = key_fingerprints(params, &vk, &preprocessing)?;
+        std::fs::create_dir_all(&self.dir)?;
+        // a proving key of the former circuit must not be taken for one of the new shape.
+        if path.with_extension("pk").exists() {
+            std::fs::remove_file(path.with_extension("pk"))?;
+        }
+        write_key(&path.with_extension("vk"), |w| vk.write(w, self.format))?;
+        std::fs::write(path.with_extension("shape"), fingerprints)?;
+        Ok(vk)
+    }
+
+    /// the fingerprints cached for `path`, if they were taken of a circuit
+    /// with the fixed values and copies of the current one.
+    fn cached_fingerprints(&self, path: &Path, preprocessing: &[u8; 32]) -> Option<[u8; 64]> {
+        if self.regenerate {
+            return None;
+        }
+        // files of earlier versions hold the shape fingerprint only, and are regenerated.
+        let fingerprints: [u8; 64] = std::fs::read(path.with_extension("shape"))
+            .ok()?
+            .try_into()
+            .ok()?;
+        (fingerprints[32..] == preprocessing[..]).then_some(fingerprints)
+    }
+
+    fn read_pk<C: Circuit<Fr>>(
+        &self,
+        path: &Path,
+        params: &ParamsKZG<Bn256>,
+        circuit: &C,
+        preprocessing: &[u8; 32],
+    ) -> Result<Option<ProvingKey<G1Affine>>> {
+        let Some(fingerprints) = self.cached_fingerprints(path, preprocessing) else {
+            return Ok(None);
+        };
+        let Ok(file) = File::open(path.with_extension("pk")) else {
+            return Ok(None);
+        };
+        // keys of a circuit with other columns do not read back.
+        let Ok(pk) = pk_read::<G1Affine, _, _>(
+            &mut BufReader::new(file),
+            self.format,
+            params.k(),
+            circuit,
+            true,
+        ) else {
+            return Ok(None);
+        };
+        Ok((key_fingerprints(params, pk.get_vk(), preprocessing)? == fingerprints).then_some(pk))
+    }
+
+    fn read_vk<C: Circuit<Fr>>(
+        &self,
+        path: &Path,
+        params: &ParamsKZG<Bn256>,
+        circuit: &C,
+        preprocessing: &[u8; 32],
+    ) -> Result<Option<VerifyingKey<G1Affine>>> {
+        let Some(fingerprints) = self.cached_fingerprints(path, preprocessing) else {
+            return Ok(None);
+        };
+        let Ok(file) = File::open(path.with_extension("vk")) else {
+            return Ok(None);
+        };
+        let Ok(vk) = vk_read::<G1Affine, _, _>(
+            &mut BufReader::new(file),
+            self.format,
+            params.k(),
+            circuit,
+            true,
+        ) else {
+            return Ok(None);
+        };
+        Ok((key_fingerprints(params, &vk, preprocessing)? == fingerprints).then_some(vk))
+    }
+}
+
+/// the fingerprint of the shape `vk` publishes with `params`,
+/// followed by the preprocessing fingerprint of the circuit.
+fn key_fingerprints(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    preprocessing: &[u8; 32],
+) -> Result<[u8; 64]> {
+    let shape = generate_circuit_info_from_vk(params, vk)?
+        .with_srs_fingerprint(params)
+        .fingerprint()?;
+    let mut fingerprints = [0u8; 64];
+    fingerprints[..32].copy_from_slice(&shape);
+    fingerprints[32..].copy_from_slice(preprocessing);
+    Ok(fingerprints)
+}
+
+fn write_key(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
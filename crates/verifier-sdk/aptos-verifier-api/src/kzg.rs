@@ -0,0 +1,59 @@
+use anyhow::{bail, Error};
+use std::fmt;
+use std::str::FromStr;
+
+/// The KZG multi-opening scheme of a proof.
+/// The discriminants are the on-chain codes, see `GWC` and `SHPLONK` in `halo2_verifier.move`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KzgVariant {
+    Gwc = 0,
+    Shplonk = 1,
+}
+
+impl KzgVariant {
+    pub const ALL: [KzgVariant; 2] = [KzgVariant::Gwc, KzgVariant::Shplonk];
+
+    /// the `kzg_variant` argument of `verifier_api::verify_proof`.
+    pub const fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<KzgVariant> for u8 {
+    fn from(variant: KzgVariant) -> Self {
+        variant.code()
+    }
+}
+
+impl TryFrom<u8> for KzgVariant {
+    type Error = Error;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        match code {
+            0 => Ok(KzgVariant::Gwc),
+            1 => Ok(KzgVariant::Shplonk),
+            _ => bail!("unknown kzg variant code {}", code),
+        }
+    }
+}
+
+impl fmt::Display for KzgVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KzgVariant::Gwc => write!(f, "gwc"),
+            KzgVariant::Shplonk => write!(f, "shplonk"),
+        }
+    }
+}
+
+impl FromStr for KzgVariant {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "gwc" => Ok(KzgVariant::Gwc),
+            "shplonk" => Ok(KzgVariant::Shplonk),
+            _ => bail!("unknown kzg variant {}, expect gwc or shplonk", s),
+        }
+    }
+}
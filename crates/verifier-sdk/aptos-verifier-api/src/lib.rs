@@ -1,19 +1,25 @@
-use crate::types::{ArgWithTypeJSON, EntryFunctionArgumentsJSON, HexEncodedBytes};
-use anyhow::{Error, Result};
-use halo2_proofs::halo2curves::bn256::{Bn256, Fr};
+use crate::api::VerifierApi;
+use crate::kzg::KzgVariant;
+use crate::types::{EntryFunctionArgumentsJSON, MoveArg};
+use anyhow::{anyhow, Error, Result};
+use halo2_proofs::halo2curves::bn256::{Bn256, Fr, G1Affine};
 use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::plonk::Circuit;
 use halo2_proofs::poly::kzg::commitment::ParamsKZG;
-use serde_json::json;
-use shape_generator::generate_circuit_info;
+use shape_generator::{generate_circuit_info, onchain_params, verify_srs, CircuitInfo};
 
+pub mod api;
+pub mod bundle;
+pub mod key_cache;
+pub mod kzg;
+pub mod payload;
 pub mod proving;
+pub mod public_inputs;
+pub mod transaction;
 pub mod types;
 pub use shape_generator;
 
-const VERIFIER_MODULE: &str = "verifier_api";
-const PUBLISH_CIRCUIT: &str = "publish_circuit";
-const VERIFY_PROOF_FUNC: &str = "verify_proof";
+mod test;
 
 /// build publish protocol transaction payload for aptos.
 /// we only support kzg on bn254 for now.
@@ -27,82 +33,99 @@ pub fn build_publish_protocol_transaction_payload<ConcreteCircuit>(
 where
     ConcreteCircuit: Circuit<Fr>,
 {
-    let protocol = generate_circuit_info(params, circuit)?;
+    let protocol = generate_circuit_info(params, circuit)?.with_srs_fingerprint(params);
+    VerifierApi::new(verifier_address).publish_circuit(&protocol)
+}
 
+/// the arguments of `publish_circuit`, one `vector<vector<u8>>` per serialized group.
+pub fn publish_circuit_args(protocol: &CircuitInfo<G1Affine>) -> Result<Vec<MoveArg>, Error> {
     let data = protocol.serialize()?;
-
-    let args: Vec<_> = data
-        .into_iter()
-        .map(|arg| ArgWithTypeJSON {
-            arg_type: "hex".to_string(),
-            value: json!(arg
-                .into_iter()
-                .map(|i| HexEncodedBytes(i).to_string())
-                .collect::<Vec<_>>()),
-        })
-        .collect();
-    let json = EntryFunctionArgumentsJSON {
-        function_id: format!(
-            "{}::{}::{}",
-            verifier_address, VERIFIER_MODULE, PUBLISH_CIRCUIT
-        ),
-        type_args: vec![],
-        args,
-    };
-    Ok(json)
+    Ok(data.into_iter().map(MoveArg::VecHex).collect())
 }
 
 /// Build verify proof transaction payload for aptos.
 /// we only support kzg on bn254 for now.
 /// Returns a structure which can be serialized to json string,
 /// and when output the json to file, it can be run by `aptos move run`.
-
-#[allow(clippy::let_and_return)]
 pub fn build_verify_proof_transaction_payload(
     proof: Vec<u8>,
-    proof_kzg_variant: u8,
+    proof_kzg_variant: KzgVariant,
     instances: Vec<Vec<Fr>>,
     verifier_address: String,
     param_address: String,
     protocol_address: String,
 ) -> EntryFunctionArgumentsJSON {
-    let instances = instances
+    VerifierApi::new(verifier_address).verify_proof(
+        param_address,
+        protocol_address,
+        &instances,
+        proof,
+        proof_kzg_variant,
+    )
+}
+
+/// the arguments of `verify_proof`, instances are encoded column by column.
+pub fn verify_proof_args(
+    proof: Vec<u8>,
+    proof_kzg_variant: KzgVariant,
+    instances: &[Vec<Fr>],
+    param_address: String,
+    protocol_address: String,
+) -> Vec<MoveArg> {
+    vec![
+        MoveArg::Address(param_address),
+        MoveArg::Address(protocol_address),
+        MoveArg::VecVecHex(encode_instances(instances)),
+        MoveArg::Hex(proof),
+        MoveArg::U8(proof_kzg_variant.code()),
+    ]
+}
+
+/// the arguments of `verify_batch_proof`, one set of instance columns per circuit instance.
+pub fn verify_batch_proof_args(
+    proof: Vec<u8>,
+    proof_kzg_variant: KzgVariant,
+    instances: &[Vec<Vec<Fr>>],
+    param_address: String,
+    protocol_address: String,
+) -> Vec<MoveArg> {
+    vec![
+        MoveArg::Address(param_address),
+        MoveArg::Address(protocol_address),
+        MoveArg::VecVecVecHex(instances.iter().map(|i| encode_instances(i)).collect()),
+        MoveArg::Hex(proof),
+        MoveArg::U8(proof_kzg_variant.code()),
+    ]
+}
+
+fn encode_instances(instances: &[Vec<Fr>]) -> Vec<Vec<Vec<u8>>> {
+    instances
         .iter()
-        .map(|fr| {
-            fr.iter()
-                .map(|f| HexEncodedBytes(f.to_repr().as_ref().to_vec()).to_string())
-                .collect::<Vec<_>>()
+        .map(|column| {
+            column
+                .iter()
+                .map(|f| f.to_repr().as_ref().to_vec())
+                .collect()
         })
-        .collect::<Vec<_>>();
-    let json = EntryFunctionArgumentsJSON {
-        function_id: format!(
-            "{}::{}::{}",
-            verifier_address, VERIFIER_MODULE, VERIFY_PROOF_FUNC
-        ),
-        type_args: vec![],
-        args: vec![
-            ArgWithTypeJSON {
-                arg_type: "address".to_string(),
-                value: json!(param_address),
-            },
-            ArgWithTypeJSON {
-                arg_type: "address".to_string(),
-                value: json!(protocol_address),
-            },
-            ArgWithTypeJSON {
-                arg_type: "hex".to_string(),
-                value: json!(instances),
-            },
-            ArgWithTypeJSON {
-                arg_type: "hex".to_string(),
-                value: json!(HexEncodedBytes(proof.clone()).to_string()),
-            },
-            ArgWithTypeJSON {
-                arg_type: "u8".to_string(),
-                value: json!(proof_kzg_variant),
-            },
-        ],
-    };
+        .collect()
+}
+
+/// Build create params transaction payload for aptos.
+/// The points are checked and converted to the arkworks compressed encoding `param_store.move` expects.
+/// Returns a structure which can be serialized to json string,
+/// and when output the json to file, it can be run by `aptos move run`.
+pub fn build_create_params_transaction_payload(
+    params: &ParamsKZG<Bn256>,
+    verifier_address: String,
+) -> Result<EntryFunctionArgumentsJSON, Error> {
+    VerifierApi::new(verifier_address).create_params(params)
+}
 
-    json
+/// the arguments of `param_store::create`: g1, g2 and s_g2, once the params pass `verify_srs`.
+pub fn create_params_args(params: &ParamsKZG<Bn256>) -> Result<Vec<MoveArg>, Error> {
+    verify_srs(params).map_err(|e| anyhow!("invalid params: {:?}", e))?;
+    Ok(onchain_params(params)
+        .into_iter()
+        .map(MoveArg::Hex)
+        .collect())
 }
@@ -0,0 +1,167 @@
+//! BCS encoding of entry function payloads.
+//!
+//! The types follow `aptos_types::transaction` closely enough that `bcs::to_bytes`
+//! gives the same bytes as the aptos sdk, without depending on aptos-core.
+
+use crate::types::{EntryFunctionArgumentsJSON, MoveArg};
+use anyhow::{anyhow, bail, ensure, Error, Result};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct AccountAddress(pub [u8; AccountAddress::LENGTH]);
+
+impl AccountAddress {
+    pub const LENGTH: usize = 32;
+
+    pub fn to_hex_literal(&self) -> String {
+        format!("0x{}", hex::encode(self.0))
+    }
+}
+
+impl FromStr for AccountAddress {
+    type Err = Error;
+
+    /// accepts both the short (`0x1`) and the full form, with or without `0x`.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.strip_prefix("0x").unwrap_or(s);
+        ensure!(
+            !s.is_empty() && s.len() <= 2 * Self::LENGTH,
+            "invalid address length: {}",
+            s
+        );
+        let padded = format!("{:0>64}", s);
+        let mut address = [0u8; Self::LENGTH];
+        hex::decode_to_slice(padded, &mut address)?;
+        Ok(AccountAddress(address))
+    }
+}
+
+impl fmt::Display for AccountAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex_literal())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ModuleId {
+    pub address: AccountAddress,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryFunction {
+    pub module: ModuleId,
+    pub function: String,
+    /// every argument is bcs encoded on its own.
+    pub args: Vec<Vec<u8>>,
+}
+
+impl Serialize for EntryFunction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // none of the verifier entry functions is generic, so type arguments are always empty.
+        let ty_args: Vec<()> = vec![];
+        (&self.module, &self.function, ty_args, &self.args).serialize(serializer)
+    }
+}
+
+impl EntryFunction {
+    /// parse `<address>::<module>::<function>` and encode the arguments.
+    pub fn new(function_id: &str, args: &[MoveArg]) -> Result<Self> {
+        let parts: Vec<_> = function_id.split("::").collect();
+        let [address, module, function] = parts[..] else {
+            bail!("invalid function id: {}", function_id);
+        };
+        check_identifier(module)?;
+        check_identifier(function)?;
+        Ok(EntryFunction {
+            module: ModuleId {
+                address: address.parse()?,
+                name: module.to_string(),
+            },
+            function: function.to_string(),
+            args: args.iter().map(MoveArg::to_bcs).collect::<Result<_>>()?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionPayload {
+    EntryFunction(EntryFunction),
+}
+
+impl Serialize for TransactionPayload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // variants 0 and 1 are scripts and module bundles, which we never build.
+        match self {
+            TransactionPayload::EntryFunction(f) => {
+                serializer.serialize_newtype_variant("TransactionPayload", 2, "EntryFunction", f)
+            }
+        }
+    }
+}
+
+impl TransactionPayload {
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+}
+
+impl TryFrom<&EntryFunctionArgumentsJSON> for TransactionPayload {
+    type Error = Error;
+
+    fn try_from(json: &EntryFunctionArgumentsJSON) -> Result<Self> {
+        ensure!(
+            json.type_args.is_empty(),
+            "type arguments are not supported"
+        );
+        Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+            &json.function_id,
+            &json.args,
+        )?))
+    }
+}
+
+impl EntryFunctionArgumentsJSON {
+    /// the bcs encoded `TransactionPayload::EntryFunction` of the payload.
+    pub fn to_bcs(&self) -> Result<Vec<u8>> {
+        TransactionPayload::try_from(self)?.to_bytes()
+    }
+}
+
+impl MoveArg {
+    /// bcs encoding of the argument value, as expected by `EntryFunction::args`.
+    pub fn to_bcs(&self) -> Result<Vec<u8>> {
+        let bytes = match self {
+            MoveArg::Address(a) => bcs::to_bytes(&AccountAddress::from_str(a)?)?,
+            MoveArg::Bool(b) => bcs::to_bytes(b)?,
+            MoveArg::U8(v) => bcs::to_bytes(v)?,
+            MoveArg::U16(v) => bcs::to_bytes(v)?,
+            MoveArg::U32(v) => bcs::to_bytes(v)?,
+            MoveArg::U64(v) => bcs::to_bytes(v)?,
+            MoveArg::U128(v) => bcs::to_bytes(v)?,
+            MoveArg::String(s) => bcs::to_bytes(s)?,
+            MoveArg::Hex(v) => bcs::to_bytes(v)?,
+            MoveArg::VecHex(v) => bcs::to_bytes(v)?,
+            MoveArg::VecVecHex(v) => bcs::to_bytes(v)?,
+            MoveArg::VecVecVecHex(v) => bcs::to_bytes(v)?,
+        };
+        Ok(bytes)
+    }
+}
+
+fn check_identifier(s: &str) -> Result<()> {
+    let mut chars = s.chars();
+    let valid = match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!("invalid identifier: {}", s))
+    }
+}
@@ -1,24 +1,222 @@
+//! Proving and verifying with keccak256 transcripts, as `halo2_verifier.move` reads them.
+
+use crate::api::VerifierApi;
+use crate::kzg::KzgVariant;
+use crate::types::EntryFunctionArgumentsJSON;
+use anyhow::{anyhow, ensure, Result};
+use halo2_backend::transcript::{Keccak256Read, Keccak256Write};
 use halo2_proofs::arithmetic::CurveAffine;
+use halo2_proofs::halo2curves::bn256::Fr;
 use halo2_proofs::halo2curves::ff::{FromUniformBytes, WithSmallOrderMulGroup};
 use halo2_proofs::halo2curves::pairing::{Engine, MultiMillerLoop};
 use halo2_proofs::halo2curves::serde::SerdeObject;
 use halo2_proofs::halo2curves::CurveExt;
-use halo2_proofs::plonk::{create_proof, verify_proof, Circuit, ProvingKey};
+use halo2_proofs::plonk::{create_proof, verify_proof, Circuit, ProvingKey, VerifyingKey};
 use halo2_proofs::poly::commitment::{CommitmentScheme, ParamsProver, Prover, Verifier};
 use halo2_proofs::poly::kzg::commitment::{KZGCommitmentScheme, ParamsKZG};
-use halo2_proofs::poly::kzg::multiopen::{ProverGWC, VerifierGWC};
-use halo2_proofs::poly::{kzg, VerificationStrategy};
-use halo2_proofs::transcript::{
-    Challenge255, Keccak256Read, Keccak256Write, TranscriptReadBuffer, TranscriptWriterBuffer,
-};
+use halo2_proofs::poly::kzg::multiopen::{ProverGWC, ProverSHPLONK, VerifierGWC, VerifierSHPLONK};
+use halo2_proofs::poly::kzg::strategy::SingleStrategy;
+use halo2_proofs::poly::VerificationStrategy;
+use halo2_proofs::transcript::{Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer};
+use rand_core::{OsRng, RngCore};
 use std::fmt::Debug;
+use std::time::{Duration, Instant};
 
 pub use halo2_proofs::plonk::{keygen_pk, keygen_vk};
-use rand_core::OsRng;
 
+/// The outcome of `prove`.
+#[derive(Clone, Debug)]
+pub struct ProvingReport {
+    pub proof: Vec<u8>,
+    pub kzg_variant: KzgVariant,
+    pub prove_time: Duration,
+    /// `None` if the proof was not verified after proving.
+    pub verify_time: Option<Duration>,
+}
+
+impl ProvingReport {
+    pub fn proof_size(&self) -> usize {
+        self.proof.len()
+    }
+
+    /// the `verify_proof` payload of the proof, `instances` are the ones it was proved with.
+    pub fn verify_payload(
+        &self,
+        api: &VerifierApi,
+        param_address: String,
+        circuit_address: String,
+        instances: &[Vec<Fr>],
+    ) -> EntryFunctionArgumentsJSON {
+        api.verify_proof(
+            param_address,
+            circuit_address,
+            instances,
+            self.proof.clone(),
+            self.kzg_variant,
+        )
+    }
+
+    /// the `verify_batch_proof` payload of a proof created by `prove_batch`.
+    pub fn verify_batch_payload(
+        &self,
+        api: &VerifierApi,
+        param_address: String,
+        circuit_address: String,
+        instances: &[Vec<Vec<Fr>>],
+    ) -> EntryFunctionArgumentsJSON {
+        api.verify_batch_proof(
+            param_address,
+            circuit_address,
+            instances,
+            self.proof.clone(),
+            self.kzg_variant,
+        )
+    }
+}
+
+/// Prove the circuit with the given kzg variant.
+/// The randomness of the proof comes from `rng`, pass a seeded one to get the same proof every time.
+/// If `self_verify` is set, the proof is verified before it is returned.
+pub fn prove<E, ConcreteCircuit, R>(
+    params: &ParamsKZG<E>,
+    pk: &ProvingKey<E::G1Affine>,
+    circuit: ConcreteCircuit,
+    instances: &[Vec<E::Fr>],
+    kzg_variant: KzgVariant,
+    rng: R,
+    self_verify: bool,
+) -> Result<ProvingReport>
+where
+    E: Engine + Debug + MultiMillerLoop,
+    E::G1Affine:
+        SerdeObject + CurveAffine<ScalarExt = <E as Engine>::Fr, CurveExt = <E as Engine>::G1>,
+    E::G1: CurveExt<AffineExt = E::G1Affine>,
+    E::G2Affine: SerdeObject + CurveAffine,
+    ConcreteCircuit: Circuit<E::Fr>,
+    <E as Engine>::Fr: Ord + WithSmallOrderMulGroup<3> + FromUniformBytes<64>,
+    R: RngCore,
+{
+    prove_batch(
+        params,
+        pk,
+        vec![circuit],
+        &[instances.to_vec()],
+        kzg_variant,
+        rng,
+        self_verify,
+    )
+}
+
+/// Prove several instances of the same circuit into one proof.
+/// `instances` has one set of instance columns per circuit.
+pub fn prove_batch<E, ConcreteCircuit, R>(
+    params: &ParamsKZG<E>,
+    pk: &ProvingKey<E::G1Affine>,
+    circuits: Vec<ConcreteCircuit>,
+    instances: &[Vec<Vec<E::Fr>>],
+    kzg_variant: KzgVariant,
+    rng: R,
+    self_verify: bool,
+) -> Result<ProvingReport>
+where
+    E: Engine + Debug + MultiMillerLoop,
+    E::G1Affine:
+        SerdeObject + CurveAffine<ScalarExt = <E as Engine>::Fr, CurveExt = <E as Engine>::G1>,
+    E::G1: CurveExt<AffineExt = E::G1Affine>,
+    E::G2Affine: SerdeObject + CurveAffine,
+    ConcreteCircuit: Circuit<E::Fr>,
+    <E as Engine>::Fr: Ord + WithSmallOrderMulGroup<3> + FromUniformBytes<64>,
+    R: RngCore,
+{
+    ensure!(
+        circuits.len() == instances.len(),
+        "{} circuits but {} sets of instances",
+        circuits.len(),
+        instances.len()
+    );
+    let prove_start = Instant::now();
+    let proof = match kzg_variant {
+        KzgVariant::Gwc => create_keccak_proof::<KZGCommitmentScheme<E>, ProverGWC<E>, _, _>(
+            params, pk, &circuits, instances, rng,
+        ),
+        KzgVariant::Shplonk => {
+            create_keccak_proof::<KZGCommitmentScheme<E>, ProverSHPLONK<E>, _, _>(
+                params, pk, &circuits, instances, rng,
+            )
+        }
+    }?;
+    let prove_time = prove_start.elapsed();
+
+    let verify_time = if self_verify {
+        let verify_start = Instant::now();
+        verify_batch(params, pk.get_vk(), instances, &proof, kzg_variant)?;
+        Some(verify_start.elapsed())
+    } else {
+        None
+    };
+
+    Ok(ProvingReport {
+        proof,
+        kzg_variant,
+        prove_time,
+        verify_time,
+    })
+}
+
+/// Verify a proof created by `prove`.
+pub fn verify<E>(
+    params: &ParamsKZG<E>,
+    vk: &VerifyingKey<E::G1Affine>,
+    instances: &[Vec<E::Fr>],
+    proof: &[u8],
+    kzg_variant: KzgVariant,
+) -> Result<()>
+where
+    E: Engine + Debug + MultiMillerLoop,
+    E::G1Affine:
+        SerdeObject + CurveAffine<ScalarExt = <E as Engine>::Fr, CurveExt = <E as Engine>::G1>,
+    E::G1: CurveExt<AffineExt = E::G1Affine>,
+    E::G2Affine: SerdeObject + CurveAffine,
+    <E as Engine>::Fr: Ord + WithSmallOrderMulGroup<3> + FromUniformBytes<64>,
+{
+    verify_batch(params, vk, &[instances.to_vec()], proof, kzg_variant)
+}
+
+/// Verify a proof created by `prove_batch`.
+pub fn verify_batch<E>(
+    params: &ParamsKZG<E>,
+    vk: &VerifyingKey<E::G1Affine>,
+    instances: &[Vec<Vec<E::Fr>>],
+    proof: &[u8],
+    kzg_variant: KzgVariant,
+) -> Result<()>
+where
+    E: Engine + Debug + MultiMillerLoop,
+    E::G1Affine:
+        SerdeObject + CurveAffine<ScalarExt = <E as Engine>::Fr, CurveExt = <E as Engine>::G1>,
+    E::G1: CurveExt<AffineExt = E::G1Affine>,
+    E::G2Affine: SerdeObject + CurveAffine,
+    <E as Engine>::Fr: Ord + WithSmallOrderMulGroup<3> + FromUniformBytes<64>,
+{
+    let params = params.verifier_params();
+    match kzg_variant {
+        KzgVariant::Gwc => {
+            verify_keccak_proof::<KZGCommitmentScheme<E>, VerifierGWC<E>, SingleStrategy<E>>(
+                params, vk, instances, proof,
+            )
+        }
+        KzgVariant::Shplonk => {
+            verify_keccak_proof::<KZGCommitmentScheme<E>, VerifierSHPLONK<E>, SingleStrategy<E>>(
+                params, vk, instances, proof,
+            )
+        }
+    }
+}
+
+#[deprecated(note = "use `prove`, which returns errors instead of panicking")]
 pub fn prove_with_gwc_and_keccak256<E, ConcreteCircuit>(
     circuit: ConcreteCircuit,
-    instance: &[&[E::Fr]],
+    instance: &[Vec<E::Fr>],
     params: &ParamsKZG<E>,
     pk: ProvingKey<E::G1Affine>,
 ) -> Vec<u8>
@@ -31,65 +229,51 @@ where
     ConcreteCircuit: Circuit<E::Fr>,
     <E as Engine>::Fr: Ord + WithSmallOrderMulGroup<3> + FromUniformBytes<64>,
 {
-    prove_vm_circuit::<
-        KZGCommitmentScheme<E>,
-        ProverGWC<E>,
-        VerifierGWC<E>,
-        kzg::strategy::SingleStrategy<E>,
-        _,
-    >(circuit, instance, params, pk)
+    prove(params, &pk, circuit, instance, KzgVariant::Gwc, OsRng, true)
+        .expect("proof generation should not fail")
+        .proof
 }
 
-// prove circuit,return it proof.
-fn prove_vm_circuit<
+fn create_keccak_proof<
     'params,
     Scheme: CommitmentScheme,
     P: Prover<'params, Scheme>,
-    V: Verifier<'params, Scheme>,
-    Strategy: VerificationStrategy<'params, Scheme, V>,
     ConcreteCircuit: Circuit<Scheme::Scalar>,
+    R: RngCore,
 >(
-    circuit: ConcreteCircuit,
-    instance: &[&[Scheme::Scalar]],
     params: &'params Scheme::ParamsProver,
-    pk: ProvingKey<Scheme::Curve>,
-) -> Vec<u8>
+    pk: &ProvingKey<Scheme::Curve>,
+    circuits: &[ConcreteCircuit],
+    instances: &[Vec<Vec<Scheme::Scalar>>],
+    rng: R,
+) -> Result<Vec<u8>>
 where
     <Scheme as CommitmentScheme>::ParamsVerifier: 'params,
     <Scheme as CommitmentScheme>::Scalar: WithSmallOrderMulGroup<3> + FromUniformBytes<64>,
 {
-    let mut transcript = Keccak256Write::<_, _, Challenge255<_>>::init(vec![]);
-    // Create a proof
-    let prove_start = std::time::Instant::now();
-
-    create_proof::<Scheme, P, _, _, _, _>(
-        params,
-        &pk,
-        &[circuit],
-        &[instance],
-        OsRng,
-        &mut transcript,
-    )
-    .expect("proof generation should not fail");
-    let proof: Vec<u8> = transcript.finalize();
-    println!("proof size {} bytes", proof.len());
-    let prove_time = std::time::Instant::now().duration_since(prove_start);
-    println!("prove time: {} ms", prove_time.as_millis());
-
-    let verifier_params = params.verifier_params();
-    let strategy = Strategy::new(verifier_params);
-    let mut transcript = Keccak256Read::<_, _, Challenge255<_>>::init(&proof[..]);
-    let verify_start = std::time::Instant::now();
-    let result = verify_proof(
-        verifier_params,
-        pk.get_vk(),
-        strategy,
-        &[instance],
-        &mut transcript,
-    );
+    let mut transcript = Keccak256Write::<Vec<u8>, _, Challenge255<_>>::init(vec![]);
+    create_proof::<Scheme, P, _, _, _, _>(params, pk, circuits, instances, rng, &mut transcript)
+        .map_err(|e| anyhow!("proof generation failed: {:?}", e))?;
+    Ok(transcript.finalize())
+}
 
-    let verify_time = std::time::Instant::now().duration_since(verify_start);
-    println!("verify time: {} ms", verify_time.as_millis());
-    assert!(result.is_ok());
-    proof
+fn verify_keccak_proof<
+    'params,
+    Scheme: CommitmentScheme,
+    V: Verifier<'params, Scheme>,
+    Strategy: VerificationStrategy<'params, Scheme, V>,
+>(
+    params: &'params Scheme::ParamsVerifier,
+    vk: &VerifyingKey<Scheme::Curve>,
+    instances: &[Vec<Vec<Scheme::Scalar>>],
+    proof: &[u8],
+) -> Result<()>
+where
+    <Scheme as CommitmentScheme>::Scalar: WithSmallOrderMulGroup<3> + FromUniformBytes<64>,
+{
+    let strategy = Strategy::new(params);
+    let mut transcript = Keccak256Read::<_, _, Challenge255<_>>::init(proof);
+    verify_proof(params, vk, strategy, instances, &mut transcript)
+        .map_err(|e| anyhow!("proof verification failed: {:?}", e))?;
+    Ok(())
 }
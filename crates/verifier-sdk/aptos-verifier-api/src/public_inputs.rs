@@ -0,0 +1,154 @@
+//! The zkMove VM public-input layout, as defined by `public_inputs.move`.
+//!
+//! Every pushed value takes one row across the four instance columns:
+//! the packed sub index, the header flag, and the low and high 128 bits of the value.
+
+use anyhow::{anyhow, ensure, Result};
+use halo2_proofs::halo2curves::bn256::Fr;
+use halo2_proofs::halo2curves::ff::{Field, PrimeField};
+
+pub const NUM_COLUMNS: usize = 4;
+pub const SUB_INDEX_LIMBS: usize = 8;
+pub const LIMB_BITS: u32 = 16;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicInputs {
+    columns: Vec<Vec<Fr>>,
+}
+
+impl Default for PublicInputs {
+    /// a single dummy row of zeros, same as `public_inputs::default`.
+    fn default() -> Self {
+        Self {
+            columns: vec![vec![Fr::ZERO]; NUM_COLUMNS],
+        }
+    }
+}
+
+impl PublicInputs {
+    pub fn empty() -> Self {
+        Self {
+            columns: vec![vec![]; NUM_COLUMNS],
+        }
+    }
+
+    /// read the instances from the bytes passed to `verifier_api`, column by column.
+    pub fn new(bytes: &[Vec<Vec<u8>>]) -> Result<Self> {
+        ensure!(
+            bytes.len() == NUM_COLUMNS,
+            "expect {} columns, got {}",
+            NUM_COLUMNS,
+            bytes.len()
+        );
+        let columns = bytes
+            .iter()
+            .map(|column| column.iter().map(|b| fr_from_bytes(b)).collect())
+            .collect::<Result<_>>()?;
+        Ok(Self { columns })
+    }
+
+    pub fn push_u8(&mut self, v: u8) {
+        self.push_word(v as u128, 0);
+    }
+
+    pub fn push_u16(&mut self, v: u16) {
+        self.push_word(v as u128, 0);
+    }
+
+    pub fn push_u32(&mut self, v: u32) {
+        self.push_word(v as u128, 0);
+    }
+
+    pub fn push_u64(&mut self, v: u64) {
+        self.push_word(v as u128, 0);
+    }
+
+    pub fn push_u128(&mut self, v: u128) {
+        self.push_word(v, 0);
+    }
+
+    /// `v` is the big-endian representation of a move `u256`.
+    pub fn push_u256(&mut self, v: [u8; 32]) {
+        let (lo, hi) = u256_to_lo_hi(&v);
+        self.push_word(lo, hi);
+    }
+
+    pub fn push_bool(&mut self, v: bool) {
+        self.push_word(v as u128, 0);
+    }
+
+    /// push a row with an explicit sub index and header flag.
+    pub fn push_with_sub_index(
+        &mut self,
+        sub_index_limbs: &[u64],
+        header: bool,
+        word_lo: u128,
+        word_hi: u128,
+    ) -> Result<()> {
+        let row = [
+            pack_sub_index(sub_index_limbs)?,
+            Fr::from(header as u64),
+            Fr::from_u128(word_lo),
+            Fr::from_u128(word_hi),
+        ];
+        for (column, value) in self.columns.iter_mut().zip(row) {
+            column.push(value);
+        }
+        Ok(())
+    }
+
+    fn push_word(&mut self, word_lo: u128, word_hi: u128) {
+        self.push_with_sub_index(&[0], false, word_lo, word_hi)
+            .expect("a zero sub index is always valid")
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.columns[0].len()
+    }
+
+    /// the instance columns, as passed to the prover.
+    pub fn as_instances(&self) -> Vec<Vec<Fr>> {
+        self.columns.clone()
+    }
+
+    /// the field elements column by column, 32 little-endian bytes each, same as `public_inputs::serialize_to_bytes`.
+    pub fn serialize_to_bytes(&self) -> Vec<u8> {
+        self.columns
+            .iter()
+            .flatten()
+            .flat_map(|f| f.to_repr())
+            .collect()
+    }
+}
+
+/// pack the limbs into a field element, the first limb is the least significant.
+pub fn pack_sub_index(limbs: &[u64]) -> Result<Fr> {
+    ensure!(
+        limbs.len() <= (255 / LIMB_BITS) as usize,
+        "too many sub index limbs: {}",
+        limbs.len()
+    );
+    let base = Fr::from(1u64 << LIMB_BITS);
+    let mut value = Fr::ZERO;
+    let mut multiplier = Fr::ONE;
+    for limb in limbs {
+        ensure!(*limb <= 0xFFFF, "sub index limb {} exceeds 16 bits", limb);
+        value += Fr::from(*limb) * multiplier;
+        multiplier *= base;
+    }
+    Ok(value)
+}
+
+/// split a big-endian u256 into its low and high 128 bits.
+pub fn u256_to_lo_hi(v: &[u8; 32]) -> (u128, u128) {
+    let hi = u128::from_be_bytes(v[..16].try_into().unwrap());
+    let lo = u128::from_be_bytes(v[16..].try_into().unwrap());
+    (lo, hi)
+}
+
+fn fr_from_bytes(bytes: &[u8]) -> Result<Fr> {
+    let repr: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| anyhow!("expect 32 bytes for a field element, got {}", bytes.len()))?;
+    Option::from(Fr::from_repr(repr)).ok_or_else(|| anyhow!("non-canonical field element"))
+}
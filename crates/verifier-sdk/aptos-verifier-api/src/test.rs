@@ -0,0 +1,884 @@
+#[cfg(test)]
+mod tests {
+    use crate::api::{
+        entry_function_signature, Deployment, VerifierApi, CREATE_PARAMS, PUBLISH_CIRCUIT,
+    };
+    use crate::kzg::KzgVariant;
+    use crate::payload::{AccountAddress, EntryFunction, TransactionPayload};
+    use crate::public_inputs::{pack_sub_index, PublicInputs};
+    use crate::transaction::{
+        account_address, derive_object_address, derive_resource_account_address, LocalAccount,
+        RawTransaction,
+    };
+    use crate::types::{ArgWithTypeJSON, EntryFunctionArgumentsJSON, MoveArg};
+    use crate::{
+        build_create_params_transaction_payload, build_verify_proof_transaction_payload,
+        verify_proof_args,
+    };
+    use halo2_proofs::halo2curves::bn256::{Bn256, Fr, G1Affine};
+    use halo2_proofs::halo2curves::ff::PrimeField;
+    use halo2_proofs::poly::commitment::Params;
+    use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+    use rand_core::OsRng;
+    use serde_json::{json, Value};
+    use shape_generator::{srs_fingerprint, CircuitInfo};
+
+    // the payloads of example vector-mul as vk-gen-examples writes them,
+    // for the circuit and proof of `verifier_api_test.move`.
+    const PUBLISH_JSON: &str = include_str!("../testdata/VectorMul-publish-circuit.json");
+    const VERIFY_JSON: &str = include_str!("../testdata/VectorMul-verify-proof-gwc.json");
+    const DEPLOYER: &str = "0xcfae5b6bd579e7aff4274aeca434bb500c024b89c139b545c6eeb27bfafea8c1";
+
+    #[test]
+    fn test_parse_publish_json() {
+        let payload: EntryFunctionArgumentsJSON = serde_json::from_str(PUBLISH_JSON).unwrap();
+        assert_eq!(
+            payload.function_id,
+            format!("{}::verifier_api::publish_circuit", DEPLOYER)
+        );
+        assert_eq!(
+            arg_types(&payload),
+            entry_function_params(VERIFIER_API_MOVE, "publish_circuit")
+        );
+        let MoveArg::VecHex(general_info) = &payload.args[0] else {
+            panic!("expect the general info, got {:?}", payload.args[0]);
+        };
+        assert_eq!(general_info.len(), 12);
+        assert_eq!(general_info[3], vec![12]);
+        // vector-mul has no lookups or shuffles.
+        assert_eq!(payload.args[7..], vec![MoveArg::VecHex(vec![]); 4]);
+        assert_eq!(
+            payload.args[6],
+            MoveArg::VecHex(vec![hex::decode("080200070803000301060302").unwrap()])
+        );
+        assert_eq!(
+            serde_json::to_string_pretty(&payload).unwrap(),
+            PUBLISH_JSON.trim_end()
+        );
+        assert_eq!(
+            VerifierApi::new(DEPLOYER)
+                .read_payload(PUBLISH_JSON)
+                .unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn test_parse_verify_json() {
+        let payload: EntryFunctionArgumentsJSON = serde_json::from_str(VERIFY_JSON).unwrap();
+        let MoveArg::Hex(proof) = payload.args[3].clone() else {
+            panic!("expect the proof, got {:?}", payload.args[3]);
+        };
+        assert_eq!(
+            serde_json::to_string_pretty(&payload).unwrap(),
+            VERIFY_JSON.trim_end()
+        );
+
+        let built = build_verify_proof_transaction_payload(
+            proof,
+            KzgVariant::Gwc,
+            vec![vec![Fr::from(6); 3]],
+            DEPLOYER.to_string(),
+            DEPLOYER.to_string(),
+            DEPLOYER.to_string(),
+        );
+        assert_eq!(built, payload);
+    }
+
+    #[test]
+    fn test_move_arg_json() {
+        let cases = [
+            (MoveArg::Bool(true), json!({"type": "bool", "value": true})),
+            (MoveArg::U16(7), json!({"type": "u16", "value": 7})),
+            (MoveArg::U32(7), json!({"type": "u32", "value": 7})),
+            (
+                MoveArg::U64(u64::MAX),
+                json!({"type": "u64", "value": "18446744073709551615"}),
+            ),
+            (
+                MoveArg::U128(u128::MAX),
+                json!({"type": "u128", "value": "340282366920938463463374607431768211455"}),
+            ),
+            (
+                MoveArg::String("vector_mul".to_string()),
+                json!({"type": "string", "value": "vector_mul"}),
+            ),
+        ];
+        for (arg, expected) in cases {
+            assert_eq!(serde_json::to_value(&arg).unwrap(), expected);
+            assert_eq!(serde_json::from_value::<MoveArg>(expected).unwrap(), arg);
+        }
+
+        // numbers are accepted either way.
+        let arg: MoveArg = serde_json::from_value(json!({"type": "u64", "value": 5})).unwrap();
+        assert_eq!(arg, MoveArg::U64(5));
+        let arg: MoveArg = serde_json::from_value(json!({"type": "u8", "value": "5"})).unwrap();
+        assert_eq!(arg, MoveArg::U8(5));
+
+        assert!(serde_json::from_value::<MoveArg>(json!({"type": "u8", "value": 256})).is_err());
+        assert!(
+            serde_json::from_value::<MoveArg>(json!({"type": "hex", "value": "0xzz"})).is_err()
+        );
+        assert!(
+            serde_json::from_value::<MoveArg>(json!({"type": "signer", "value": "0x1"})).is_err()
+        );
+
+        // one proof of two circuit instances, each with a single instance column.
+        let batch = MoveArg::VecVecVecHex(vec![vec![vec![vec![1]]], vec![vec![vec![2], vec![3]]]]);
+        let expected = json!({"type": "hex", "value": [[["0x01"]], [["0x02", "0x03"]]]});
+        assert_eq!(serde_json::to_value(&batch).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<MoveArg>(expected).unwrap(), batch);
+        assert!(serde_json::from_value::<MoveArg>(
+            json!({"type": "hex", "value": [[["0x01"]], ["0x02"]]})
+        )
+        .is_err());
+        assert!(
+            serde_json::from_value::<MoveArg>(json!({"type": "hex", "value": [[[["0x01"]]]]}))
+                .is_err()
+        );
+
+        let raw: ArgWithTypeJSON = MoveArg::Hex(vec![]).into();
+        assert_eq!(raw.value, Value::String("0x".to_string()));
+        assert_eq!(
+            verify_proof_args(vec![], KzgVariant::Gwc, &[], "0x1".into(), "0x2".into())[2],
+            MoveArg::VecVecHex(vec![])
+        );
+    }
+
+    #[test]
+    fn test_verify_proof_bcs_payload() {
+        let payload = build_verify_proof_transaction_payload(
+            vec![0xde, 0xad, 0xbe, 0xef],
+            KzgVariant::Shplonk,
+            vec![vec![Fr::from(1)]],
+            "0x1".to_string(),
+            "0x1".to_string(),
+            "0x2".to_string(),
+        );
+        let expected = concat!(
+            "02",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0c76657269666965725f617069",
+            "0c7665726966795f70726f6f66",
+            "00",
+            "05",
+            "200000000000000000000000000000000000000000000000000000000000000001",
+            "200000000000000000000000000000000000000000000000000000000000000002",
+            "230101200100000000000000000000000000000000000000000000000000000000000000",
+            "0504deadbeef",
+            "0101",
+        );
+        assert_eq!(hex::encode(payload.to_bcs().unwrap()), expected);
+    }
+
+    #[test]
+    fn test_publish_circuit_bcs_payload() {
+        let payload = EntryFunctionArgumentsJSON::new(
+            "0xcafe::verifier_api::publish_circuit".to_string(),
+            vec![
+                MoveArg::VecHex(vec![hex::decode("080200070803000301060302").unwrap()]),
+                MoveArg::VecHex(vec![]),
+            ],
+        );
+        let expected = concat!(
+            "02",
+            "000000000000000000000000000000000000000000000000000000000000cafe",
+            "0c76657269666965725f617069",
+            "0f7075626c6973685f63697263756974",
+            "00",
+            "02",
+            "0e010c080200070803000301060302",
+            "0100",
+        );
+        assert_eq!(hex::encode(payload.to_bcs().unwrap()), expected);
+        // parsing the json file gives the same payload.
+        let json = serde_json::to_string(&payload).unwrap();
+        let parsed: EntryFunctionArgumentsJSON = serde_json::from_str(&json).unwrap();
+        assert_eq!(hex::encode(parsed.to_bcs().unwrap()), expected);
+    }
+
+    #[test]
+    fn test_bcs_payload_errors() {
+        let address: AccountAddress = "0x1".parse().unwrap();
+        assert_eq!(address.0[31], 1);
+        assert_eq!(
+            address.to_string(),
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert!("0x".parse::<AccountAddress>().is_err());
+        assert!(format!("0x{}", "1".repeat(65))
+            .parse::<AccountAddress>()
+            .is_err());
+
+        assert!(EntryFunction::new("0x1::verifier_api", &[]).is_err());
+        assert!(EntryFunction::new("0x1::verifier-api::verify_proof", &[]).is_err());
+        assert!(EntryFunction::new("0x1::verifier_api::1verify", &[]).is_err());
+        assert!(EntryFunction::new("zz::verifier_api::verify", &[]).is_err());
+
+        let mut payload =
+            EntryFunctionArgumentsJSON::new("0x1::coin::transfer".to_string(), vec![]);
+        payload
+            .type_args
+            .push("0x1::aptos_coin::AptosCoin".to_string());
+        assert!(TransactionPayload::try_from(&payload).is_err());
+    }
+
+    // generated with python `cryptography` and `hashlib` from the same inputs.
+    const SENDER: &str = "b6bbeddd149a70b603089e5a1e4e259c39d6cbfb0139265b1d4bd79eaf790b03";
+    const PUBLIC_KEY: &str = "79b5562e8fe654f94078b112e8a98ba7901f853ae695bed7e0e3910bad049664";
+    const SIGNATURE: &str = "7f78f003990f7f7830f450c445351ccefae5690fcd025c00dbf186dbfcbe904be133af0c32c673a157adc1d63828410cad72a374e964cd88db8d95480effb104";
+    const TXN_HASH: &str = "73885092c54a5e17727c78530bab65731fbf7af82fdf7d3c4e1182abba42aa82";
+
+    fn test_account() -> LocalAccount {
+        let private_key: Vec<u8> = (1..=32).collect();
+        LocalAccount::from_private_key(private_key.try_into().unwrap())
+    }
+
+    fn raw_transaction(account: &LocalAccount) -> RawTransaction {
+        let payload = build_verify_proof_transaction_payload(
+            vec![0xde, 0xad, 0xbe, 0xef],
+            KzgVariant::Shplonk,
+            vec![vec![Fr::from(1)]],
+            "0x1".to_string(),
+            "0x1".to_string(),
+            "0x2".to_string(),
+        );
+        RawTransaction {
+            sender: account.address(),
+            sequence_number: 7,
+            payload: TransactionPayload::try_from(&payload).unwrap(),
+            max_gas_amount: 200_000,
+            gas_unit_price: 100,
+            expiration_timestamp_secs: 1_700_000_000,
+            chain_id: 2,
+        }
+    }
+
+    #[test]
+    fn test_account_address() {
+        let account = test_account();
+        assert_eq!(hex::encode(account.public_key()), PUBLIC_KEY);
+        assert_eq!(hex::encode(account.address().0), SENDER);
+        assert_eq!(account_address(&account.public_key()), account.address());
+
+        let from_hex = LocalAccount::from_private_key_hex(
+            "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20",
+        )
+        .unwrap();
+        assert_eq!(from_hex.address(), account.address());
+        assert!(LocalAccount::from_private_key_hex("0x0102").is_err());
+    }
+
+    #[test]
+    fn test_sign_transaction() {
+        let account = test_account();
+        let raw = raw_transaction(&account);
+        let message = raw.signing_message().unwrap();
+        assert_eq!(
+            hex::encode(&message[..32]),
+            "b5e97db07fa0bd0e5598aa3643a9bc6f6693bddc1a9fec9e674a461eaa00b193"
+        );
+
+        let payload = raw.payload.to_bytes().unwrap();
+        let mut expected_raw = hex::decode(SENDER).unwrap();
+        expected_raw.extend(7u64.to_le_bytes());
+        expected_raw.extend(&payload);
+        expected_raw.extend(200_000u64.to_le_bytes());
+        expected_raw.extend(100u64.to_le_bytes());
+        expected_raw.extend(1_700_000_000u64.to_le_bytes());
+        expected_raw.push(2);
+        assert_eq!(&message[32..], &expected_raw[..]);
+
+        let signed = raw.sign(&account).unwrap();
+        assert_eq!(hex::encode(signed.signature), SIGNATURE);
+        signed.verify().unwrap();
+
+        let mut expected_signed = expected_raw;
+        expected_signed.extend([0x00, 0x20]);
+        expected_signed.extend(hex::decode(PUBLIC_KEY).unwrap());
+        expected_signed.push(0x40);
+        expected_signed.extend(hex::decode(SIGNATURE).unwrap());
+        assert_eq!(signed.to_bytes().unwrap(), expected_signed);
+        assert_eq!(hex::encode(signed.hash().unwrap()), TXN_HASH);
+
+        let mut tampered = signed.clone();
+        tampered.raw_txn.sequence_number += 1;
+        assert!(tampered.verify().is_err());
+    }
+
+    #[test]
+    fn test_sign_with_wrong_sender() {
+        let account = test_account();
+        let mut raw = raw_transaction(&account);
+        raw.sender = "0x1".parse::<AccountAddress>().unwrap();
+        assert!(raw.sign(&account).is_err());
+    }
+
+    #[test]
+    fn test_create_params_payload() {
+        let params = ParamsKZG::<Bn256>::setup(4, OsRng);
+        let payload = build_create_params_transaction_payload(&params, "0x1".to_string()).unwrap();
+        assert_eq!(payload.function_id, "0x1::param_store::create");
+        assert_eq!(payload.args.len(), 3);
+        // the generators, as in the tutorial.
+        let mut g1 = vec![0u8; 32];
+        g1[0] = 1;
+        assert_eq!(payload.args[0], MoveArg::Hex(g1));
+        assert_eq!(
+            payload.args[1],
+            MoveArg::Hex(hex::decode("edf692d95cbdde46ddda5ef7d422436779445c5e66006a42761e1f12efde0018c212f3aeb785e49712e7a9353349aaf1255dfb31b7bf60723a480d9293938e19").unwrap())
+        );
+        assert!(matches!(&payload.args[2], MoveArg::Hex(s_g2) if s_g2.len() == 64));
+
+        // s_g2 of another setup does not match the g1 powers.
+        let other = ParamsKZG::<Bn256>::setup(4, OsRng);
+        let mixed = params.from_parts(
+            params.k(),
+            params.get_g().to_vec(),
+            None,
+            params.g2(),
+            other.s_g2(),
+        );
+        assert!(build_create_params_transaction_payload(&mixed, "0x1".to_string()).is_err());
+        let degenerate = params.from_parts(
+            params.k(),
+            params.get_g().to_vec(),
+            None,
+            params.g2(),
+            params.g2(),
+        );
+        assert!(build_create_params_transaction_payload(&degenerate, "0x1".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_publish_circuit_with_srs() {
+        let params = ParamsKZG::<Bn256>::setup(4, OsRng);
+        let other = ParamsKZG::<Bn256>::setup(4, OsRng);
+        let api = VerifierApi::new("0xcafe");
+        let general_info = |info: &CircuitInfo<G1Affine>| info.serialize().unwrap()[0].clone();
+
+        // the shape serializes the same with or without a fingerprint, as `publish_circuit` takes it.
+        let unbound = empty_circuit_info();
+        let bound = empty_circuit_info().with_srs_fingerprint(&params);
+        assert_eq!(general_info(&unbound).len(), 12);
+        assert_eq!(general_info(&bound), general_info(&unbound));
+        assert_eq!(
+            api.publish_circuit(&bound).unwrap(),
+            api.publish_circuit(&unbound).unwrap()
+        );
+        // but the fingerprint of the shape covers it.
+        assert_ne!(bound.fingerprint().unwrap(), unbound.fingerprint().unwrap());
+
+        // the fingerprint goes last, and is the one of the params the shape was keyed with.
+        let payload = api
+            .publish_circuit_with_srs(&params, &bound, &Deployment::Sender)
+            .unwrap();
+        assert_eq!(
+            payload.function_id,
+            "0xcafe::verifier_api::publish_circuit_with_srs"
+        );
+        assert_eq!(payload.args.len(), 12);
+        assert_eq!(
+            payload.args[11],
+            MoveArg::Hex(srs_fingerprint(&params).to_vec())
+        );
+        assert!(api
+            .publish_circuit_with_srs(&other, &bound, &Deployment::Sender)
+            .is_err());
+        // a shape without a fingerprint is bound to the params given.
+        let payload = api
+            .publish_circuit_with_srs(&other, &unbound, &Deployment::Sender)
+            .unwrap();
+        assert_eq!(
+            payload.args[11],
+            MoveArg::Hex(srs_fingerprint(&other).to_vec())
+        );
+    }
+
+    // the same pushes as `public_inputs_tests::test_specific_values_correctly_encoded`.
+    #[test]
+    fn test_public_inputs_encoding() {
+        let mut pi = PublicInputs::empty();
+        pi.push_bool(true);
+        pi.push_u8(255);
+        pi.push_u64(123456789);
+        pi.push_u128(u128::MAX);
+        pi.push_u256(
+            hex::decode("1234567890ABCDEF1234567890ABCDEF1234567890ABCDEF1234567890ABCDEF")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        );
+        let mut top_bit = [0u8; 32];
+        top_bit[0] = 0x80;
+        pi.push_u256(top_bit);
+        pi.push_bool(false);
+
+        let num_rows = 7;
+        assert_eq!(pi.row_count(), num_rows);
+        let bytes = pi.serialize_to_bytes();
+        assert_eq!(bytes.len(), num_rows * 4 * 32);
+        let cell = |col: usize, row: usize| {
+            let offset = col * num_rows * 32 + row * 32;
+            hex::encode(&bytes[offset..offset + 32])
+        };
+        let small = |v: u64| hex::encode(Fr::from(v).to_repr());
+
+        let zero = small(0);
+        for row in 0..num_rows {
+            assert_eq!(cell(0, row), zero);
+            assert_eq!(cell(1, row), zero);
+        }
+        assert_eq!(cell(2, 0), small(1));
+        assert_eq!(cell(2, 1), small(255));
+        assert_eq!(
+            cell(2, 2),
+            "15cd5b0700000000000000000000000000000000000000000000000000000000"
+        );
+        assert_eq!(
+            cell(2, 3),
+            "ffffffffffffffffffffffffffffffff00000000000000000000000000000000"
+        );
+        assert_eq!(cell(3, 3), zero);
+        let half = "efcdab9078563412efcdab907856341200000000000000000000000000000000";
+        assert_eq!(cell(2, 4), half);
+        assert_eq!(cell(3, 4), half);
+        assert_eq!(cell(2, 5), zero);
+        assert_eq!(
+            cell(3, 5),
+            "0000000000000000000000000000008000000000000000000000000000000000"
+        );
+        assert_eq!(cell(2, 6), zero);
+
+        // the bytes passed to `verifier_api` read back to the same inputs.
+        let instances: Vec<Vec<Vec<u8>>> = pi
+            .as_instances()
+            .iter()
+            .map(|column| column.iter().map(|f| f.to_repr().to_vec()).collect())
+            .collect();
+        assert_eq!(PublicInputs::new(&instances).unwrap(), pi);
+        assert!(PublicInputs::new(&instances[..3]).is_err());
+    }
+
+    #[test]
+    fn test_public_inputs_rows() {
+        assert_eq!(PublicInputs::default().row_count(), 1);
+        assert_eq!(PublicInputs::empty().row_count(), 0);
+
+        assert_eq!(pack_sub_index(&[]).unwrap(), Fr::from(0));
+        assert_eq!(pack_sub_index(&[1, 2]).unwrap(), Fr::from(1 + (2 << 16)));
+        assert!(pack_sub_index(&[0x10000]).is_err());
+        assert!(pack_sub_index(&[0; 15]).is_ok());
+        assert!(pack_sub_index(&[0; 16]).is_err());
+
+        let mut pi = PublicInputs::empty();
+        pi.push_with_sub_index(&[3, 1], true, 7, 0).unwrap();
+        let instances = pi.as_instances();
+        assert_eq!(instances.len(), 4);
+        assert_eq!(instances[0], vec![Fr::from(3 + (1 << 16))]);
+        assert_eq!(instances[1], vec![Fr::from(1)]);
+        assert_eq!(instances[2], vec![Fr::from(7)]);
+        assert_eq!(instances[3], vec![Fr::from(0)]);
+    }
+
+    const HALO2_VERIFIER_MOVE: &str =
+        include_str!("../../../../packages/verifier/sources/halo2_verifier.move");
+    const VERIFIER_API_MOVE: &str =
+        include_str!("../../../../packages/api/sources/verifier_api.move");
+
+    /// the value of `const <name>: u8 = <value>;` in a move module.
+    fn move_u8_const(source: &str, name: &str) -> u8 {
+        let prefix = format!("const {}: u8 =", name);
+        let line = source
+            .lines()
+            .map(str::trim)
+            .find(|l| l.starts_with(&prefix))
+            .unwrap_or_else(|| panic!("const {} not found", name));
+        line[prefix.len()..]
+            .trim()
+            .trim_end_matches(';')
+            .trim()
+            .parse()
+            .unwrap()
+    }
+
+    /// the kzg variant `verifier_api::<function>` forwards to `verify_proof`.
+    fn forwarded_variant(function: &str) -> u8 {
+        let start = VERIFIER_API_MOVE
+            .find(&format!("fun {}(", function))
+            .unwrap();
+        let body = &VERIFIER_API_MOVE[start..];
+        let call = body.find("verify_proof(").unwrap();
+        let args = &body[call..body[call..].find(')').unwrap() + call];
+        args.rsplit(',').next().unwrap().trim().parse().unwrap()
+    }
+
+    #[test]
+    fn test_kzg_variant_matches_move() {
+        assert_eq!(
+            move_u8_const(HALO2_VERIFIER_MOVE, "GWC"),
+            KzgVariant::Gwc.code()
+        );
+        assert_eq!(
+            move_u8_const(HALO2_VERIFIER_MOVE, "SHPLONK"),
+            KzgVariant::Shplonk.code()
+        );
+        assert_eq!(
+            forwarded_variant("verify_proof_gwc"),
+            KzgVariant::Gwc.code()
+        );
+        assert_eq!(
+            forwarded_variant("verify_proof_shplonk"),
+            KzgVariant::Shplonk.code()
+        );
+
+        for variant in KzgVariant::ALL {
+            assert_eq!(KzgVariant::try_from(u8::from(variant)).unwrap(), variant);
+            assert_eq!(variant.to_string().parse::<KzgVariant>().unwrap(), variant);
+        }
+        assert!(KzgVariant::try_from(2).is_err());
+        assert!("kzg".parse::<KzgVariant>().is_err());
+        assert_eq!("GWC".parse::<KzgVariant>().unwrap(), KzgVariant::Gwc);
+    }
+
+    /// the parameter types of `public entry fun <function>` in a move module, the signer excluded.
+    fn entry_function_params(source: &str, function: &str) -> Vec<String> {
+        let start = source
+            .find(&format!("public entry fun {}(", function))
+            .unwrap_or_else(|| panic!("entry function {} not found", function));
+        let params = &source[source[start..].find('(').unwrap() + start + 1..];
+        let params = &params[..params.find(')').unwrap()];
+        params
+            .split(',')
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| p.split_once(':').unwrap().1.trim().to_string())
+            .filter(|t| t != "&signer")
+            .collect()
+    }
+
+    fn arg_types(payload: &EntryFunctionArgumentsJSON) -> Vec<String> {
+        payload
+            .args
+            .iter()
+            .map(|arg| arg.move_type().to_string())
+            .collect()
+    }
+
+    fn empty_circuit_info() -> CircuitInfo<G1Affine> {
+        CircuitInfo {
+            vk_transcript_repr: Fr::from(0),
+            fixed_commitments: vec![],
+            permutation_commitments: vec![],
+            k: 4,
+            max_num_query_of_advice_column: 0,
+            cs_degree: 0,
+            num_fixed_columns: 0,
+            num_instance_columns: 0,
+            advice_column_phase: vec![],
+            challenge_phase: vec![],
+            fields_pool: vec![],
+            gates: vec![],
+            advice_queries: vec![],
+            instance_queries: vec![],
+            fixed_queries: vec![],
+            permutation_columns: vec![],
+            lookups: vec![],
+            shuffles: vec![],
+            srs_fingerprint: None,
+        }
+    }
+
+    fn deployments() -> [Deployment; 3] {
+        [
+            Deployment::Sender,
+            Deployment::Object(b"vector_mul".to_vec()),
+            Deployment::ResourceAccount(b"vector_mul".to_vec()),
+        ]
+    }
+
+    #[test]
+    fn test_deployment_addresses() {
+        let deployer: AccountAddress = "0xcafe".parse().unwrap();
+        let [sender, object, resource_account] = deployments();
+        assert_eq!(sender.address(&deployer), deployer);
+        // `check_deployment_addresses` of the Move tests checks the same addresses on chain.
+        assert_eq!(
+            object.address(&deployer).to_string(),
+            "0xc183ceffac1717fd3df0013d8c67795e70012f16ff25551798842f3a58106605"
+        );
+        assert_eq!(
+            resource_account.address(&deployer).to_string(),
+            "0x6969abf356b79e60df8103deac1ce46a0dbdf01d1716f9635ef18aa9f4fdd972"
+        );
+        assert_eq!(
+            derive_object_address(&deployer, b"vector_mul"),
+            object.address(&deployer)
+        );
+        assert_eq!(
+            derive_resource_account_address(&deployer, b"vector_mul"),
+            resource_account.address(&deployer)
+        );
+        // the seed and the deployer both matter.
+        assert_ne!(
+            derive_object_address(&deployer, b"two_chip"),
+            object.address(&deployer)
+        );
+        assert_ne!(
+            derive_object_address(&"0xbeef".parse().unwrap(), b"vector_mul"),
+            object.address(&deployer)
+        );
+
+        // the seed goes right after the signer.
+        let payload = VerifierApi::new("0xcafe")
+            .publish_circuit_to(&empty_circuit_info(), &object)
+            .unwrap();
+        assert_eq!(
+            payload.function_id,
+            "0xcafe::verifier_api::publish_circuit_to_object"
+        );
+        assert_eq!(payload.args[0], MoveArg::Hex(b"vector_mul".to_vec()));
+        assert_eq!(payload.args.len(), 12);
+    }
+
+    #[test]
+    fn test_verifier_api_matches_move() {
+        const PARAM_STORE_MOVE: &str =
+            include_str!("../../../../packages/api/sources/param_store.move");
+        let api = VerifierApi::new("0xcafe");
+        let instances = vec![vec![Fr::from(1), Fr::from(2)]];
+        let verify = |api: &VerifierApi| {
+            vec![
+                api.verify_proof(
+                    "0x1".to_string(),
+                    "0x2".to_string(),
+                    &instances,
+                    vec![0xde, 0xad],
+                    KzgVariant::Shplonk,
+                ),
+                api.verify_proof_gwc("0x1".to_string(), "0x2".to_string(), &instances, vec![]),
+                api.verify_proof_shplonk("0x1".to_string(), "0x2".to_string(), &instances, vec![]),
+                api.mock_verify_proof(
+                    "0x1".to_string(),
+                    "0x2".to_string(),
+                    &instances,
+                    vec![],
+                    KzgVariant::Gwc,
+                ),
+            ]
+        };
+
+        let batch = |api: &VerifierApi| {
+            api.verify_batch_proof(
+                "0x1".to_string(),
+                "0x2".to_string(),
+                &[instances.clone(), instances.clone()],
+                vec![],
+                KzgVariant::Gwc,
+            )
+        };
+
+        let params = ParamsKZG::<Bn256>::setup(4, OsRng);
+        let mut payloads = verify(&api);
+        payloads.push(batch(&api));
+        for deployment in deployments() {
+            payloads.push(
+                api.publish_circuit_to(&empty_circuit_info(), &deployment)
+                    .unwrap(),
+            );
+            payloads.push(
+                api.publish_circuit_with_srs(&params, &empty_circuit_info(), &deployment)
+                    .unwrap(),
+            );
+        }
+        for payload in &payloads {
+            let function = payload.function_id.rsplit("::").next().unwrap();
+            assert!(payload.function_id.starts_with("0xcafe::verifier_api::"));
+            assert_eq!(
+                arg_types(payload),
+                entry_function_params(VERIFIER_API_MOVE, function),
+                "{}",
+                function
+            );
+            assert!(TransactionPayload::try_from(payload).is_ok());
+        }
+
+        let create = api.create_params(&params).unwrap();
+        assert_eq!(create.function_id, "0xcafe::param_store::create");
+        for deployment in deployments() {
+            let create = api.create_params_to(&params, &deployment).unwrap();
+            let function = create.function_id.rsplit("::").next().unwrap();
+            assert!(create.function_id.starts_with("0xcafe::param_store::"));
+            assert_eq!(
+                arg_types(&create),
+                entry_function_params(PARAM_STORE_MOVE, function),
+                "{}",
+                function
+            );
+        }
+
+        // every verify call goes to the mock in mock mode, with the variant filled in.
+        let mock = api.clone().with_module("my_verifier").with_mock(true);
+        for payload in verify(&mock) {
+            assert_eq!(
+                payload.function_id,
+                "0xcafe::my_verifier::mock_verify_proof"
+            );
+            assert_eq!(
+                arg_types(&payload),
+                entry_function_params(VERIFIER_API_MOVE, "mock_verify_proof")
+            );
+        }
+        let mock_batch = batch(&mock);
+        assert_eq!(
+            mock_batch.function_id,
+            "0xcafe::my_verifier::mock_verify_batch_proof"
+        );
+        assert_eq!(
+            arg_types(&mock_batch),
+            entry_function_params(VERIFIER_API_MOVE, "mock_verify_batch_proof")
+        );
+        let renamed = api
+            .clone()
+            .with_publish_circuit_function("publish_vk")
+            .with_verify_proof_function("verify");
+        assert_eq!(
+            verify(&renamed)[0].function_id,
+            "0xcafe::verifier_api::verify"
+        );
+        assert_eq!(verify(&renamed)[1..], verify(&api)[1..]);
+        assert_eq!(
+            renamed
+                .publish_circuit(&empty_circuit_info())
+                .unwrap()
+                .function_id,
+            "0xcafe::verifier_api::publish_vk"
+        );
+        assert_eq!(
+            renamed
+                .publish_circuit_to(&empty_circuit_info(), &deployments()[1])
+                .unwrap(),
+            payloads[6]
+        );
+        let shplonk =
+            mock.verify_proof_shplonk("0x1".to_string(), "0x2".to_string(), &instances, vec![]);
+        assert_eq!(shplonk.args[4], MoveArg::U8(KzgVariant::Shplonk.code()));
+
+        // the free functions are the default api.
+        assert_eq!(
+            build_verify_proof_transaction_payload(
+                vec![0xde, 0xad],
+                KzgVariant::Shplonk,
+                instances.clone(),
+                "0xcafe".to_string(),
+                "0x1".to_string(),
+                "0x2".to_string(),
+            ),
+            payloads[0]
+        );
+    }
+
+    #[test]
+    fn test_empty_nested_vectors_round_trip() {
+        const PARAM_STORE_MOVE: &str =
+            include_str!("../../../../packages/api/sources/param_store.move");
+        for function in [
+            "publish_circuit",
+            "publish_circuit_to_object",
+            "publish_circuit_to_resource_account",
+            "verify_proof",
+            "verify_proof_gwc",
+            "verify_proof_shplonk",
+            "mock_verify_proof",
+            "verify_batch_proof",
+            "mock_verify_batch_proof",
+        ] {
+            assert_eq!(
+                entry_function_signature(&format!("0x1::verifier_api::{}", function)).unwrap(),
+                entry_function_params(VERIFIER_API_MOVE, function),
+                "{}",
+                function
+            );
+        }
+        for function in [
+            CREATE_PARAMS,
+            "create_to_object",
+            "create_to_resource_account",
+        ] {
+            assert_eq!(
+                entry_function_signature(&format!("0x1::param_store::{}", function)).unwrap(),
+                entry_function_params(PARAM_STORE_MOVE, function),
+                "{}",
+                function
+            );
+        }
+        // functions of other modules are not taken for those of the package.
+        assert!(entry_function_signature("0x1::verifier_api::register").is_none());
+        assert!(entry_function_signature("0x1::coin::create").is_none());
+        assert!(entry_function_signature("verify_proof").is_none());
+
+        // a circuit without lookups or shuffles, and proofs without instances.
+        let api = VerifierApi::new("0xcafe");
+        let mut payloads = vec![
+            api.verify_proof("0x1".into(), "0x2".into(), &[], vec![], KzgVariant::Gwc),
+            api.verify_batch_proof("0x1".into(), "0x2".into(), &[], vec![], KzgVariant::Gwc),
+            api.verify_batch_proof(
+                "0x1".into(),
+                "0x2".into(),
+                &[vec![]],
+                vec![],
+                KzgVariant::Gwc,
+            ),
+        ];
+        for deployment in deployments() {
+            payloads.push(
+                api.publish_circuit_to(&empty_circuit_info(), &deployment)
+                    .unwrap(),
+            );
+        }
+        for payload in payloads {
+            let json = serde_json::to_string(&payload).unwrap();
+            assert_eq!(
+                serde_json::from_str::<EntryFunctionArgumentsJSON>(&json).unwrap(),
+                payload
+            );
+        }
+
+        // renamed functions read back with the api which built them.
+        let renamed = api
+            .clone()
+            .with_module("my_verifier")
+            .with_verify_proof_function("verify");
+        let verify = renamed.verify_proof("0x1".into(), "0x2".into(), &[], vec![], KzgVariant::Gwc);
+        let json = serde_json::to_string(&verify).unwrap();
+        assert_ne!(
+            serde_json::from_str::<EntryFunctionArgumentsJSON>(&json).unwrap(),
+            verify
+        );
+        assert_eq!(renamed.read_payload(&json).unwrap(), verify);
+        assert!(renamed.signature("0xcafe::verifier_api::verify").is_none());
+        assert_eq!(
+            renamed.signature("0xcafe::param_store::create"),
+            entry_function_signature("0xcafe::param_store::create")
+        );
+
+        let publish = api.publish_circuit(&empty_circuit_info()).unwrap();
+        assert!(publish.function_id.ends_with(PUBLISH_CIRCUIT));
+        assert!(publish.args.contains(&MoveArg::VecVecHex(vec![])));
+
+        // on its own, an argument is only as deep as its first items.
+        let raw: ArgWithTypeJSON =
+            serde_json::from_value(json!({"type": "hex", "value": [[]]})).unwrap();
+        assert_eq!(
+            MoveArg::try_from(raw.clone()).unwrap(),
+            MoveArg::VecVecHex(vec![vec![]])
+        );
+        assert_eq!(
+            MoveArg::from_json_as(raw.clone(), "vector<vector<vector<vector<u8>>>>").unwrap(),
+            MoveArg::VecVecVecHex(vec![vec![]])
+        );
+        assert!(MoveArg::from_json_as(raw, "vector<u8>").is_err());
+        let raw: ArgWithTypeJSON =
+            serde_json::from_value(json!({"type": "u8", "value": 1})).unwrap();
+        assert_eq!(MoveArg::from_json_as(raw, "u8").unwrap(), MoveArg::U8(1));
+    }
+}
@@ -0,0 +1,169 @@
+//! Offline construction and signing of aptos transactions.
+//!
+//! A `RawTransaction` wraps a payload with the sender and the gas settings.
+//! It is signed with a local Ed25519 key, so that the `SignedTransaction`
+//! can be built on an air-gapped machine and broadcast from elsewhere.
+
+use crate::payload::{AccountAddress, TransactionPayload};
+use anyhow::{ensure, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::ser::SerializeStructVariant;
+use serde::{Serialize, Serializer};
+use sha3::{Digest, Sha3_256};
+
+/// the scheme id appended to the public key when deriving the authentication key.
+const ED25519_SCHEME: u8 = 0;
+/// the scheme id of `object::create_object_address`.
+const OBJECT_FROM_SEED_SCHEME: u8 = 0xFE;
+/// the scheme id of `account::create_resource_address`.
+const RESOURCE_ACCOUNT_SCHEME: u8 = 0xFF;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RawTransaction {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub payload: TransactionPayload,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub expiration_timestamp_secs: u64,
+    /// 1 for mainnet, 2 for testnet.
+    pub chain_id: u8,
+}
+
+impl RawTransaction {
+    /// the bytes the sender signs: the hashed domain separator followed by the bcs encoded transaction.
+    pub fn signing_message(&self) -> Result<Vec<u8>> {
+        let mut message = domain_prefix("RawTransaction").to_vec();
+        message.extend(bcs::to_bytes(self)?);
+        Ok(message)
+    }
+
+    pub fn sign(self, account: &LocalAccount) -> Result<SignedTransaction> {
+        ensure!(
+            self.sender == account.address(),
+            "sender {} does not match the signing key {}",
+            self.sender,
+            account.address()
+        );
+        let signature = account.key.sign(&self.signing_message()?);
+        Ok(SignedTransaction {
+            raw_txn: self,
+            public_key: account.public_key(),
+            signature: signature.to_bytes(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedTransaction {
+    pub raw_txn: RawTransaction,
+    pub public_key: [u8; 32],
+    pub signature: [u8; 64],
+}
+
+impl Serialize for SignedTransaction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.raw_txn, Ed25519Authenticator(self)).serialize(serializer)
+    }
+}
+
+/// `TransactionAuthenticator::Ed25519`, keys and signatures are encoded as byte vectors.
+struct Ed25519Authenticator<'a>(&'a SignedTransaction);
+
+impl Serialize for Ed25519Authenticator<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s =
+            serializer.serialize_struct_variant("TransactionAuthenticator", 0, "Ed25519", 2)?;
+        s.serialize_field("public_key", self.0.public_key.as_slice())?;
+        s.serialize_field("signature", self.0.signature.as_slice())?;
+        s.end()
+    }
+}
+
+impl SignedTransaction {
+    /// the bcs encoded transaction, as submitted to `/transactions` with `application/x.aptos.signed_transaction+bcs`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    /// the transaction hash reported by the node once it is submitted.
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        let mut hasher = Sha3_256::new();
+        hasher.update(domain_prefix("Transaction"));
+        // `Transaction::UserTransaction`
+        hasher.update([0u8]);
+        hasher.update(self.to_bytes()?);
+        Ok(hasher.finalize().into())
+    }
+
+    pub fn verify(&self) -> Result<()> {
+        let key = VerifyingKey::from_bytes(&self.public_key)?;
+        let signature = Signature::from_bytes(&self.signature);
+        key.verify(&self.raw_txn.signing_message()?, &signature)?;
+        ensure!(
+            self.raw_txn.sender == account_address(&self.public_key),
+            "sender does not match the public key"
+        );
+        Ok(())
+    }
+}
+
+/// an account backed by a local Ed25519 private key.
+pub struct LocalAccount {
+    key: SigningKey,
+}
+
+impl LocalAccount {
+    pub fn from_private_key(private_key: [u8; 32]) -> Self {
+        Self {
+            key: SigningKey::from_bytes(&private_key),
+        }
+    }
+
+    /// parse the hex private key, as stored in `.aptos/config.yaml`.
+    pub fn from_private_key_hex(private_key: &str) -> Result<Self> {
+        let private_key = private_key.trim();
+        let private_key = private_key.strip_prefix("0x").unwrap_or(private_key);
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(private_key, &mut bytes)?;
+        Ok(Self::from_private_key(bytes))
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        self.key.verifying_key().to_bytes()
+    }
+
+    pub fn address(&self) -> AccountAddress {
+        account_address(&self.public_key())
+    }
+}
+
+/// the address of an account created from a single Ed25519 key, that is, its authentication key.
+pub fn account_address(public_key: &[u8; 32]) -> AccountAddress {
+    let mut hasher = Sha3_256::new();
+    hasher.update(public_key);
+    hasher.update([ED25519_SCHEME]);
+    AccountAddress(hasher.finalize().into())
+}
+
+/// the address of the named object `object::create_named_object(creator, seed)` creates.
+pub fn derive_object_address(creator: &AccountAddress, seed: &[u8]) -> AccountAddress {
+    derive_address(creator, seed, OBJECT_FROM_SEED_SCHEME)
+}
+
+/// the address of the resource account `account::create_resource_account(source, seed)` creates.
+pub fn derive_resource_account_address(source: &AccountAddress, seed: &[u8]) -> AccountAddress {
+    derive_address(source, seed, RESOURCE_ACCOUNT_SCHEME)
+}
+
+fn derive_address(source: &AccountAddress, seed: &[u8], scheme: u8) -> AccountAddress {
+    let mut hasher = Sha3_256::new();
+    hasher.update(source.0);
+    hasher.update(seed);
+    hasher.update([scheme]);
+    AccountAddress(hasher.finalize().into())
+}
+
+fn domain_prefix(name: &str) -> [u8; 32] {
+    Sha3_256::digest(format!("APTOS::{}", name).as_bytes()).into()
+}
@@ -1,25 +1,287 @@
+use crate::api::entry_function_signature;
+use anyhow::{anyhow, bail, Error};
 use core::fmt;
 use serde::{Deserialize, Serialize};
-#[derive(Deserialize, Serialize)]
+use serde_json::{json, Value};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 /// JSON file format for function arguments.
 pub struct ArgWithTypeJSON {
     #[serde(rename = "type")]
-    pub(crate) arg_type: String,
-    pub(crate) value: serde_json::Value,
+    pub arg_type: String,
+    pub value: Value,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "RawEntryFunctionArgumentsJSON")]
 /// JSON file format for entry function arguments.
+/// The arguments of the entry functions of the package are read as the types of their
+/// signature, see `entry_function_signature`, so that empty nested vectors read back.
+/// Payloads of a module or functions under other names are read with `VerifierApi::read_payload`.
 pub struct EntryFunctionArgumentsJSON {
     pub function_id: String,
     pub type_args: Vec<String>,
-    pub args: Vec<ArgWithTypeJSON>,
+    pub args: Vec<MoveArg>,
+}
+
+#[derive(Deserialize)]
+struct RawEntryFunctionArgumentsJSON {
+    function_id: String,
+    type_args: Vec<String>,
+    args: Vec<ArgWithTypeJSON>,
+}
+
+impl TryFrom<RawEntryFunctionArgumentsJSON> for EntryFunctionArgumentsJSON {
+    type Error = Error;
+
+    fn try_from(raw: RawEntryFunctionArgumentsJSON) -> Result<Self, Self::Error> {
+        raw.into_payload(entry_function_signature)
+    }
+}
+
+impl RawEntryFunctionArgumentsJSON {
+    fn into_payload(
+        self,
+        signature: impl FnOnce(&str) -> Option<Vec<&'static str>>,
+    ) -> Result<EntryFunctionArgumentsJSON, Error> {
+        let args = match signature(&self.function_id) {
+            Some(signature) if signature.len() == self.args.len() => raw
+                .args
+                .into_iter()
+                .zip(signature)
+                .map(|(arg, move_type)| MoveArg::from_json_as(arg, move_type))
+                .collect::<Result<_, _>>()?,
+            _ => raw
+                .args
+                .into_iter()
+                .map(MoveArg::try_from)
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(EntryFunctionArgumentsJSON {
+            function_id: self.function_id,
+            type_args: self.type_args,
+            args,
+        })
+    }
+}
+
+impl EntryFunctionArgumentsJSON {
+    pub fn new(function_id: String, args: Vec<MoveArg>) -> Self {
+        Self {
+            function_id,
+            type_args: vec![],
+            args,
+        }
+    }
+
+    /// parse a payload, its arguments read as the move types `signature` gives for its function id.
+    /// Without a signature of as many types as there are arguments, they are read on their own.
+    pub fn from_json_with(
+        json: &str,
+        signature: impl FnOnce(&str) -> Option<Vec<&'static str>>,
+    ) -> Result<Self, Error> {
+        serde_json::from_str::<RawEntryFunctionArgumentsJSON>(json)?.into_payload(signature)
+    }
+}
+
+/// An entry function argument.
+/// It serializes to the `{"type": .., "value": ..}` form read by `aptos move run --json-file`.
+/// Byte vectors use the `hex` type, nested vectors are json arrays of hex strings.
+/// Read on its own, the nesting is judged by the first items, so an empty nested vector
+/// reads back as a `VecHex`. Use `from_json_as` when the move type is known.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(into = "ArgWithTypeJSON", try_from = "ArgWithTypeJSON")]
+pub enum MoveArg {
+    Address(String),
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    String(String),
+    /// `vector<u8>`
+    Hex(Vec<u8>),
+    /// `vector<vector<u8>>`
+    VecHex(Vec<Vec<u8>>),
+    /// `vector<vector<vector<u8>>>`
+    VecVecHex(Vec<Vec<Vec<u8>>>),
+    /// `vector<vector<vector<vector<u8>>>>`
+    VecVecVecHex(Vec<Vec<Vec<Vec<u8>>>>),
+}
+
+impl MoveArg {
+    /// the move type of the argument, as written in an entry function signature.
+    pub fn move_type(&self) -> &'static str {
+        match self {
+            MoveArg::Address(_) => "address",
+            MoveArg::Bool(_) => "bool",
+            MoveArg::U8(_) => "u8",
+            MoveArg::U16(_) => "u16",
+            MoveArg::U32(_) => "u32",
+            MoveArg::U64(_) => "u64",
+            MoveArg::U128(_) => "u128",
+            MoveArg::String(_) => "0x1::string::String",
+            MoveArg::Hex(_) => "vector<u8>",
+            MoveArg::VecHex(_) => "vector<vector<u8>>",
+            MoveArg::VecVecHex(_) => "vector<vector<vector<u8>>>",
+            MoveArg::VecVecVecHex(_) => "vector<vector<vector<vector<u8>>>>",
+        }
+    }
+
+    /// read an argument of the given move type, as written in an entry function signature.
+    /// Hex arrays are read as nested as the type says, other types are read as by serde.
+    pub fn from_json_as(arg: ArgWithTypeJSON, move_type: &str) -> Result<Self, Error> {
+        match (arg.arg_type.as_str(), u8_vector_depth(move_type)) {
+            ("hex", Some(depth)) => parse_hex(&arg.value, depth),
+            _ => MoveArg::try_from(arg),
+        }
+    }
+}
+
+impl From<MoveArg> for ArgWithTypeJSON {
+    fn from(arg: MoveArg) -> Self {
+        let hex = |bytes: Vec<u8>| HexEncodedBytes(bytes).to_string();
+        let (arg_type, value) = match arg {
+            MoveArg::Address(a) => ("address", json!(a)),
+            MoveArg::Bool(b) => ("bool", json!(b)),
+            MoveArg::U8(v) => ("u8", json!(v)),
+            MoveArg::U16(v) => ("u16", json!(v)),
+            MoveArg::U32(v) => ("u32", json!(v)),
+            // large integers are written as strings, as the aptos cli does.
+            MoveArg::U64(v) => ("u64", json!(v.to_string())),
+            MoveArg::U128(v) => ("u128", json!(v.to_string())),
+            MoveArg::String(s) => ("string", json!(s)),
+            MoveArg::Hex(bytes) => ("hex", json!(hex(bytes))),
+            MoveArg::VecHex(v) => ("hex", json!(v.into_iter().map(hex).collect::<Vec<_>>())),
+            MoveArg::VecVecHex(v) => (
+                "hex",
+                json!(v
+                    .into_iter()
+                    .map(|v| v.into_iter().map(hex).collect::<Vec<_>>())
+                    .collect::<Vec<_>>()),
+            ),
+            MoveArg::VecVecVecHex(v) => (
+                "hex",
+                json!(v
+                    .into_iter()
+                    .map(|v| v
+                        .into_iter()
+                        .map(|v| v.into_iter().map(hex).collect::<Vec<_>>())
+                        .collect::<Vec<_>>())
+                    .collect::<Vec<_>>()),
+            ),
+        };
+        ArgWithTypeJSON {
+            arg_type: arg_type.to_string(),
+            value,
+        }
+    }
+}
+
+impl TryFrom<ArgWithTypeJSON> for MoveArg {
+    type Error = Error;
+
+    fn try_from(arg: ArgWithTypeJSON) -> Result<Self, Self::Error> {
+        let value = arg.value;
+        let arg = match arg.arg_type.as_str() {
+            "address" => MoveArg::Address(as_str(&value)?.to_string()),
+            "bool" => MoveArg::Bool(
+                value
+                    .as_bool()
+                    .ok_or_else(|| anyhow!("expect a bool, got {}", value))?,
+            ),
+            "u8" => MoveArg::U8(as_int(&value)?),
+            "u16" => MoveArg::U16(as_int(&value)?),
+            "u32" => MoveArg::U32(as_int(&value)?),
+            "u64" => MoveArg::U64(as_int(&value)?),
+            "u128" => MoveArg::U128(as_int(&value)?),
+            "string" => MoveArg::String(as_str(&value)?.to_string()),
+            "hex" => parse_hex(&value, hex_depth(&value))?,
+            t => bail!("unsupported argument type {}", t),
+        };
+        Ok(arg)
+    }
+}
+
+fn as_str(value: &Value) -> Result<&str, Error> {
+    value
+        .as_str()
+        .ok_or_else(|| anyhow!("expect a string, got {}", value))
+}
+
+fn as_hex(value: &Value) -> Result<Vec<u8>, Error> {
+    Ok(HexEncodedBytes::parse(as_str(value)?)?.0)
+}
+
+fn as_array<T>(value: &Value, item: impl Fn(&Value) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+    match value {
+        Value::Array(items) => items.iter().map(item).collect(),
+        _ => bail!("expect an array, got {}", value),
+    }
+}
+
+/// hex strings nested `depth` arrays deep.
+fn parse_hex(value: &Value, depth: usize) -> Result<MoveArg, Error> {
+    let arg = match depth {
+        0 => MoveArg::Hex(as_hex(value)?),
+        1 => MoveArg::VecHex(as_array(value, as_hex)?),
+        2 => MoveArg::VecVecHex(as_array(value, |v| as_array(v, as_hex))?),
+        3 => MoveArg::VecVecVecHex(as_array(value, |v| as_array(v, |v| as_array(v, as_hex)))?),
+        _ => bail!("hex arrays are nested too deep: {}", value),
+    };
+    Ok(arg)
+}
+
+/// how many vectors `vector<u8>` is nested in, `None` if it is not a byte vector type.
+fn u8_vector_depth(move_type: &str) -> Option<usize> {
+    let mut inner = move_type.trim();
+    let mut depth = 0;
+    while let Some(item) = inner
+        .strip_prefix("vector<")
+        .and_then(|t| t.strip_suffix('>'))
+    {
+        depth += 1;
+        inner = item.trim();
+    }
+    (inner == "u8" && depth > 0).then(|| depth - 1)
+}
+
+/// how deep the hex strings are nested in arrays, judged by the first items.
+/// an empty array counts as an array of hex strings.
+fn hex_depth(value: &Value) -> usize {
+    match value {
+        Value::Array(items) => 1 + items.first().map_or(0, hex_depth),
+        _ => 0,
+    }
+}
+
+/// integers may be given either as json numbers or as strings.
+fn as_int<T>(value: &Value) -> Result<T, Error>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let s = match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.clone(),
+        _ => bail!("expect an integer, got {}", value),
+    };
+    Ok(s.parse()?)
 }
 
 /// Hex encoded bytes to allow for having bytes represented in JSON
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct HexEncodedBytes(pub Vec<u8>);
 
+impl HexEncodedBytes {
+    /// parse hex, with or without the `0x` prefix.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let s = s.strip_prefix("0x").unwrap_or(s);
+        Ok(HexEncodedBytes(hex::decode(s)?))
+    }
+}
+
 impl fmt::Display for HexEncodedBytes {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "0x{}", hex::encode(&self.0))?;
@@ -0,0 +1,596 @@
+//! Build a verifier shape without going through halo2's `Circuit` trait.
+//!
+//! Circuits coming from another IR or another PLONKish tool describe their columns,
+//! queries and constraints with [`ShapeBuilder`] and the small [`Expr`] DSL.
+//! The builder checks the description and lowers it to the same [`CircuitInfo`]
+//! that `generate_circuit_info` produces, so it serializes the same way.
+
+use crate::{
+    encode_field, CircuitInfo, Column, ColumnQuery, Gate, IndexType, IndexedExpression, Lookup,
+    Shuffle,
+};
+use halo2_middleware::circuit::ChallengeMid as Challenge;
+use halo2_proofs::arithmetic::{CurveAffine, Field};
+use halo2_proofs::halo2curves::ff::FromUniformBytes;
+use halo2_proofs::plonk::{Error, ErrorFront};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub};
+
+const ADVICE: u8 = 1;
+const FIXED: u8 = 2;
+const INSTANCE: u8 = 3;
+
+/// Expression DSL of the builder, lowered to `IndexedExpression` by [`ShapeBuilder::build`].
+#[derive(Clone, Debug)]
+pub enum Expr<F: Field> {
+    Constant(F),
+    /// a declared query, see [`ShapeBuilder::query`].
+    Query(Column, i32),
+    Challenge(usize),
+    Negated(Box<Expr<F>>),
+    Sum(Box<Expr<F>>, Box<Expr<F>>),
+    Product(Box<Expr<F>>, Box<Expr<F>>),
+}
+
+impl<F: Field> Expr<F> {
+    pub fn constant(f: F) -> Self {
+        Expr::Constant(f)
+    }
+
+    /// degree of the expression, as computed by halo2.
+    pub fn degree(&self) -> usize {
+        match self {
+            Expr::Constant(_) | Expr::Challenge(_) => 0,
+            Expr::Query(..) => 1,
+            Expr::Negated(a) => a.degree(),
+            Expr::Sum(a, b) => a.degree().max(b.degree()),
+            Expr::Product(a, b) => a.degree() + b.degree(),
+        }
+    }
+}
+
+impl<F: Field> Add for Expr<F> {
+    type Output = Expr<F>;
+    fn add(self, rhs: Self) -> Self::Output {
+        Expr::Sum(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: Field> Sub for Expr<F> {
+    type Output = Expr<F>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Expr::Sum(Box::new(self), Box::new(-rhs))
+    }
+}
+
+impl<F: Field> Mul for Expr<F> {
+    type Output = Expr<F>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Expr::Product(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: Field> Mul<F> for Expr<F> {
+    type Output = Expr<F>;
+    fn mul(self, rhs: F) -> Self::Output {
+        Expr::Product(Box::new(self), Box::new(Expr::Constant(rhs)))
+    }
+}
+
+impl<F: Field> Neg for Expr<F> {
+    type Output = Expr<F>;
+    fn neg(self) -> Self::Output {
+        Expr::Negated(Box::new(self))
+    }
+}
+
+pub struct ShapeBuilder<C: CurveAffine> {
+    k: u8,
+    advice_column_phase: Vec<u8>,
+    num_fixed_columns: u32,
+    num_instance_columns: u32,
+    challenge_phase: Vec<u8>,
+    advice_queries: Vec<(Column, i32)>,
+    instance_queries: Vec<(Column, i32)>,
+    fixed_queries: Vec<(Column, i32)>,
+    gates: Vec<Vec<Expr<C::Scalar>>>,
+    lookups: Vec<(Vec<Expr<C::Scalar>>, Vec<Expr<C::Scalar>>)>,
+    shuffles: Vec<(Vec<Expr<C::Scalar>>, Vec<Expr<C::Scalar>>)>,
+    permutation_columns: Vec<Column>,
+    fixed_commitments: Vec<C>,
+    permutation_commitments: Vec<C>,
+    vk_transcript_repr: Option<C::Scalar>,
+    minimum_degree: Option<u32>,
+}
+
+impl<C: CurveAffine> ShapeBuilder<C>
+where
+    C::Scalar: FromUniformBytes<64>,
+{
+    pub fn new(k: u8) -> Self {
+        Self {
+            k,
+            advice_column_phase: vec![],
+            num_fixed_columns: 0,
+            num_instance_columns: 0,
+            challenge_phase: vec![],
+            advice_queries: vec![],
+            instance_queries: vec![],
+            fixed_queries: vec![],
+            gates: vec![],
+            lookups: vec![],
+            shuffles: vec![],
+            permutation_columns: vec![],
+            fixed_commitments: vec![],
+            permutation_commitments: vec![],
+            vk_transcript_repr: None,
+            minimum_degree: None,
+        }
+    }
+
+    pub fn advice_column(&mut self, phase: u8) -> Column {
+        self.advice_column_phase.push(phase);
+        Column {
+            index: self.advice_column_phase.len() as u32 - 1,
+            column_type: ADVICE,
+        }
+    }
+
+    pub fn fixed_column(&mut self) -> Column {
+        self.num_fixed_columns += 1;
+        Column {
+            index: self.num_fixed_columns - 1,
+            column_type: FIXED,
+        }
+    }
+
+    pub fn instance_column(&mut self) -> Column {
+        self.num_instance_columns += 1;
+        Column {
+            index: self.num_instance_columns - 1,
+            column_type: INSTANCE,
+        }
+    }
+
+    /// register a challenge squeezed after the given phase.
+    pub fn challenge(&mut self, phase: u8) -> Expr<C::Scalar> {
+        self.challenge_phase.push(phase);
+        Expr::Challenge(self.challenge_phase.len() - 1)
+    }
+
+    /// declare a query of the column at the rotation, and return it as an expression.
+    /// querying the same column and rotation twice reuses the query.
+    pub fn query(&mut self, column: Column, rotation: i32) -> Expr<C::Scalar> {
+        let queries = match column.column_type {
+            ADVICE => &mut self.advice_queries,
+            FIXED => &mut self.fixed_queries,
+            _ => &mut self.instance_queries,
+        };
+        if !queries.contains(&(column, rotation)) {
+            queries.push((column, rotation));
+        }
+        Expr::Query(column, rotation)
+    }
+
+    /// add a gate, each polynomial is constrained to be zero.
+    pub fn gate(&mut self, polys: Vec<Expr<C::Scalar>>) -> &mut Self {
+        self.gates.push(polys);
+        self
+    }
+
+    pub fn lookup(
+        &mut self,
+        input_exprs: Vec<Expr<C::Scalar>>,
+        table_exprs: Vec<Expr<C::Scalar>>,
+    ) -> &mut Self {
+        self.lookups.push((input_exprs, table_exprs));
+        self
+    }
+
+    pub fn shuffle(
+        &mut self,
+        input_exprs: Vec<Expr<C::Scalar>>,
+        shuffle_exprs: Vec<Expr<C::Scalar>>,
+    ) -> &mut Self {
+        self.shuffles.push((input_exprs, shuffle_exprs));
+        self
+    }
+
+    /// add the column to the permutation argument.
+    /// like halo2, this also queries the column at the current row.
+    pub fn enable_equality(&mut self, column: Column) -> &mut Self {
+        self.query(column, 0);
+        if !self.permutation_columns.contains(&column) {
+            self.permutation_columns.push(column);
+        }
+        self
+    }
+
+    pub fn fixed_commitments(&mut self, commitments: Vec<C>) -> &mut Self {
+        self.fixed_commitments = commitments;
+        self
+    }
+
+    /// commitments of the permutation polynomials, one per permutation column.
+    pub fn permutation_commitments(&mut self, commitments: Vec<C>) -> &mut Self {
+        self.permutation_commitments = commitments;
+        self
+    }
+
+    /// the verifying key representation the prover absorbs first into its transcript.
+    /// When it is not given, it is derived from the serialized shape,
+    /// so the prover has to absorb that same value.
+    pub fn transcript_repr(&mut self, repr: C::Scalar) -> &mut Self {
+        self.vk_transcript_repr = Some(repr);
+        self
+    }
+
+    pub fn minimum_degree(&mut self, degree: u32) -> &mut Self {
+        self.minimum_degree = Some(degree);
+        self
+    }
+
+    /// check the description and lower it to a `CircuitInfo`.
+    pub fn build(&self) -> Result<CircuitInfo<C>, Error> {
+        self.check()?;
+
+        let mut fields_pool: Vec<C::Scalar> = Vec::new();
+        let mut constant_map: HashMap<Vec<u8>, u32> = HashMap::new();
+        for expr in self.all_exprs() {
+            collect_constants::<C>(expr, &mut fields_pool, &mut constant_map);
+        }
+
+        let lowering = Lowering::<C> {
+            constant_map: &constant_map,
+            use_u8_index_for_fields: fields_pool.len() < 256,
+            use_u8_index_for_query: self.advice_queries.len() < 256
+                && self.fixed_queries.len() < 256
+                && self.instance_queries.len() < 256,
+            builder: self,
+        };
+
+        let gates = self
+            .gates
+            .iter()
+            .map(|polys| {
+                Ok(Gate {
+                    polys: lowering.lower_all(polys)?,
+                    _phantom: PhantomData,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let lookups = self
+            .lookups
+            .iter()
+            .map(|(input, table)| {
+                Ok(Lookup {
+                    input_exprs: lowering.lower_all(input)?,
+                    table_exprs: lowering.lower_all(table)?,
+                    _phantom: PhantomData,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let shuffles = self
+            .shuffles
+            .iter()
+            .map(|(input, shuffle)| {
+                Ok(Shuffle {
+                    input_exprs: lowering.lower_all(input)?,
+                    shuffle_exprs: lowering.lower_all(shuffle)?,
+                    _phantom: PhantomData,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let to_column_queries = |queries: &[(Column, i32)]| {
+            queries
+                .iter()
+                .map(|(column, rotation)| ColumnQuery {
+                    column: *column,
+                    rotation: halo2_proofs::poly::Rotation(*rotation).into(),
+                })
+                .collect()
+        };
+
+        let mut info = CircuitInfo {
+            vk_transcript_repr: self.vk_transcript_repr.unwrap_or(C::Scalar::ZERO),
+            fixed_commitments: self.fixed_commitments.clone(),
+            permutation_commitments: self.permutation_commitments.clone(),
+            k: self.k,
+            max_num_query_of_advice_column: self
+                .advice_queries
+                .iter()
+                .fold(BTreeMap::<u32, u32>::new(), |mut m, (c, _)| {
+                    *m.entry(c.index).or_default() += 1;
+                    m
+                })
+                .values()
+                .max()
+                .cloned()
+                .unwrap_or_default(),
+            cs_degree: self.degree(),
+            num_fixed_columns: self.num_fixed_columns as u64,
+            num_instance_columns: self.num_instance_columns as u64,
+            advice_column_phase: self.advice_column_phase.clone(),
+            challenge_phase: self.challenge_phase.clone(),
+            fields_pool,
+            gates,
+            advice_queries: to_column_queries(&self.advice_queries),
+            instance_queries: to_column_queries(&self.instance_queries),
+            fixed_queries: to_column_queries(&self.fixed_queries),
+            permutation_columns: self.permutation_columns.clone(),
+            lookups,
+            shuffles,
+            srs_fingerprint: None,
+        };
+
+        if self.vk_transcript_repr.is_none() {
+            let data = info
+                .serialize()
+                .map_err(|e| ErrorFront::Other(e.to_string()))?;
+            let mut hasher = blake2b_simd::Params::new()
+                .hash_length(64)
+                .personal(b"Halo2-Verify-Key")
+                .to_state();
+            for bytes in data.iter().flatten() {
+                hasher.update(&(bytes.len() as u64).to_le_bytes());
+                hasher.update(bytes);
+            }
+            info.vk_transcript_repr = C::Scalar::from_uniform_bytes(hasher.finalize().as_array());
+        }
+        Ok(info)
+    }
+
+    /// the constraint system degree, following the rules of halo2.
+    fn degree(&self) -> u32 {
+        // the permutation argument requires degree 3.
+        let mut degree = 3;
+        for (input, table) in &self.lookups {
+            let input_degree = input.iter().map(|e| e.degree()).fold(1, usize::max);
+            let table_degree = table.iter().map(|e| e.degree()).fold(1, usize::max);
+            degree = degree.max(4).max(2 + input_degree + table_degree);
+        }
+        for (input, shuffle) in &self.shuffles {
+            let input_degree = input.iter().map(|e| e.degree()).fold(1, usize::max);
+            let shuffle_degree = shuffle.iter().map(|e| e.degree()).fold(1, usize::max);
+            degree = degree.max(2 + input_degree.max(shuffle_degree));
+        }
+        for poly in self.gates.iter().flatten() {
+            degree = degree.max(poly.degree());
+        }
+        (degree as u32).max(self.minimum_degree.unwrap_or(1))
+    }
+
+    fn all_exprs(&self) -> impl Iterator<Item = &Expr<C::Scalar>> {
+        self.gates
+            .iter()
+            .flatten()
+            .chain(self.lookups.iter().flat_map(|(a, b)| a.iter().chain(b)))
+            .chain(self.shuffles.iter().flat_map(|(a, b)| a.iter().chain(b)))
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        if self.k == 0 || self.k >= 32 {
+            return Err(other(format!("k = {} is out of range", self.k)));
+        }
+
+        // advice phases must be used from 0 up without a gap,
+        // and a challenge can only be squeezed after a used phase.
+        let phases: BTreeSet<u8> = self.advice_column_phase.iter().cloned().collect();
+        if let Some(max) = phases.last() {
+            if phases.len() != *max as usize + 1 {
+                return Err(other("advice column phases are not contiguous from 0"));
+            }
+        }
+        for (i, phase) in self.challenge_phase.iter().enumerate() {
+            if !phases.contains(phase) {
+                return Err(other(format!(
+                    "challenge {} uses phase {} which has no advice column",
+                    i, phase
+                )));
+            }
+        }
+
+        for (column, _) in self
+            .advice_queries
+            .iter()
+            .chain(&self.fixed_queries)
+            .chain(&self.instance_queries)
+        {
+            self.check_column(column)?;
+        }
+        for column in &self.permutation_columns {
+            self.check_column(column)?;
+        }
+
+        for expr in self.all_exprs() {
+            self.check_expr(expr)?;
+        }
+        for (i, (input, table)) in self.lookups.iter().enumerate() {
+            if input.is_empty() || input.len() != table.len() {
+                return Err(other(format!(
+                    "lookup {} has {} input and {} table expressions",
+                    i,
+                    input.len(),
+                    table.len()
+                )));
+            }
+        }
+        for (i, (input, shuffle)) in self.shuffles.iter().enumerate() {
+            if input.is_empty() || input.len() != shuffle.len() {
+                return Err(other(format!(
+                    "shuffle {} has {} input and {} shuffle expressions",
+                    i,
+                    input.len(),
+                    shuffle.len()
+                )));
+            }
+        }
+
+        if self.fixed_commitments.len() != self.num_fixed_columns as usize {
+            return Err(other(format!(
+                "expect {} fixed commitments, got {}",
+                self.num_fixed_columns,
+                self.fixed_commitments.len()
+            )));
+        }
+        if self.permutation_commitments.len() != self.permutation_columns.len() {
+            return Err(other(format!(
+                "expect {} permutation commitments, got {}",
+                self.permutation_columns.len(),
+                self.permutation_commitments.len()
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_column(&self, column: &Column) -> Result<(), Error> {
+        let num_columns = match column.column_type {
+            ADVICE => self.advice_column_phase.len() as u32,
+            FIXED => self.num_fixed_columns,
+            INSTANCE => self.num_instance_columns,
+            t => return Err(other(format!("unknown column type {}", t))),
+        };
+        if column.index >= num_columns {
+            return Err(other(format!("column {:?} is not registered", column)));
+        }
+        Ok(())
+    }
+
+    fn check_expr(&self, expr: &Expr<C::Scalar>) -> Result<(), Error> {
+        match expr {
+            Expr::Constant(_) => Ok(()),
+            Expr::Query(column, rotation) => {
+                self.query_index(column, *rotation)?;
+                Ok(())
+            }
+            Expr::Challenge(index) => {
+                if *index >= self.challenge_phase.len() {
+                    return Err(other(format!("challenge {} is not registered", index)));
+                }
+                Ok(())
+            }
+            Expr::Negated(a) => self.check_expr(a),
+            Expr::Sum(a, b) | Expr::Product(a, b) => {
+                self.check_expr(a)?;
+                self.check_expr(b)
+            }
+        }
+    }
+
+    fn query_index(&self, column: &Column, rotation: i32) -> Result<usize, Error> {
+        let queries = match column.column_type {
+            ADVICE => &self.advice_queries,
+            FIXED => &self.fixed_queries,
+            _ => &self.instance_queries,
+        };
+        queries
+            .iter()
+            .position(|q| *q == (*column, rotation))
+            .ok_or_else(|| {
+                other(format!(
+                    "query of column {:?} at rotation {} is not declared",
+                    column, rotation
+                ))
+            })
+    }
+}
+
+struct Lowering<'a, C: CurveAffine> {
+    builder: &'a ShapeBuilder<C>,
+    constant_map: &'a HashMap<Vec<u8>, u32>,
+    use_u8_index_for_fields: bool,
+    use_u8_index_for_query: bool,
+}
+
+impl<C: CurveAffine> Lowering<'_, C> {
+    fn lower_all(
+        &self,
+        exprs: &[Expr<C::Scalar>],
+    ) -> Result<Vec<IndexedExpression<C::Scalar>>, Error> {
+        exprs.iter().map(|e| self.lower(e)).collect()
+    }
+
+    // mirrors `to_indexed_expression`, products with a constant become `Scaled`.
+    fn lower(&self, expr: &Expr<C::Scalar>) -> Result<IndexedExpression<C::Scalar>, Error> {
+        match expr {
+            Expr::Constant(f) => {
+                let index = *self
+                    .constant_map
+                    .get(&encode_field::<C>(f))
+                    .ok_or(ErrorFront::Other("Constant not found".to_string()))?;
+                Ok(IndexedExpression::ConstantIndex(
+                    index_type(index, self.use_u8_index_for_fields)?,
+                    PhantomData,
+                ))
+            }
+            Expr::Query(column, rotation) => {
+                let index = self.builder.query_index(column, *rotation)? as u32;
+                let idx = index_type(index, self.use_u8_index_for_query)?;
+                Ok(match column.column_type {
+                    ADVICE => IndexedExpression::Advice(idx),
+                    FIXED => IndexedExpression::Fixed(idx),
+                    _ => IndexedExpression::Instance(idx),
+                })
+            }
+            Expr::Challenge(index) => Ok(IndexedExpression::Challenge(Challenge {
+                index: *index,
+                phase: self.builder.challenge_phase[*index],
+            })),
+            Expr::Negated(a) => Ok(IndexedExpression::Negated(Box::new(self.lower(a)?))),
+            Expr::Sum(a, b) => Ok(IndexedExpression::Sum(
+                Box::new(self.lower(a)?),
+                Box::new(self.lower(b)?),
+            )),
+            Expr::Product(a, b) => {
+                let a = self.lower(a)?;
+                let b = self.lower(b)?;
+                Ok(match (&a, &b) {
+                    (IndexedExpression::ConstantIndex(idx, _), _) => {
+                        IndexedExpression::Scaled(Box::new(b), *idx)
+                    }
+                    (_, IndexedExpression::ConstantIndex(idx, _)) => {
+                        IndexedExpression::Scaled(Box::new(a), *idx)
+                    }
+                    _ => IndexedExpression::Product(Box::new(a), Box::new(b)),
+                })
+            }
+        }
+    }
+}
+
+fn collect_constants<C: CurveAffine>(
+    expr: &Expr<C::Scalar>,
+    fields_pool: &mut Vec<C::Scalar>,
+    constant_map: &mut HashMap<Vec<u8>, u32>,
+) {
+    match expr {
+        Expr::Constant(f) => {
+            constant_map.entry(encode_field::<C>(f)).or_insert_with(|| {
+                fields_pool.push(*f);
+                fields_pool.len() as u32 - 1
+            });
+        }
+        Expr::Query(..) | Expr::Challenge(_) => {}
+        Expr::Negated(a) => collect_constants::<C>(a, fields_pool, constant_map),
+        Expr::Sum(a, b) | Expr::Product(a, b) => {
+            collect_constants::<C>(a, fields_pool, constant_map);
+            collect_constants::<C>(b, fields_pool, constant_map);
+        }
+    }
+}
+
+fn index_type(index: u32, use_u8: bool) -> Result<IndexType, Error> {
+    if !use_u8 {
+        return Ok(IndexType::U32(index));
+    }
+    if index >= 256 {
+        return Err(ErrorFront::Other("Index exceeds limit".to_string()).into());
+    }
+    Ok(IndexType::U8(index as u8))
+}
+
+fn other(msg: impl Into<String>) -> Error {
+    ErrorFront::Other(msg.into()).into()
+}
@@ -1,15 +1,33 @@
+use halo2_backend::plonk::keygen::keygen_vk as backend_keygen_vk;
 use halo2_backend::plonk::{
     ConstraintSystemBack as ConstraintSystem, ExpressionBack as Expression, VarBack,
 };
-use halo2_middleware::circuit::ChallengeMid as Challenge;
+use halo2_frontend::circuit::compile_circuit;
+use halo2_middleware::circuit::{ChallengeMid as Challenge, CompiledCircuit};
 use halo2_proofs::arithmetic::{CurveAffine, Field};
 use halo2_proofs::halo2curves::ff::{FromUniformBytes, PrimeField};
-use halo2_proofs::plonk::{keygen_vk, Any, Circuit, Error, ErrorFront};
+use halo2_proofs::plonk::{keygen_vk, Any, Circuit, Error, ErrorFront, VerifyingKey};
 use halo2_proofs::poly::commitment::Params;
+use sha3::{Digest, Sha3_256};
 use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
 
+pub mod builder;
+pub mod lint;
+pub mod metadata;
+pub mod printer;
+pub mod srs;
 mod test;
+pub mod to_ark;
+
+pub use builder::{Expr, ShapeBuilder};
+pub use lint::{lint, Finding, LintReport, Linter, Severity};
+pub use metadata::CircuitMetadata;
+pub use printer::ShapePrinter;
+pub use srs::{
+    challenge_power, check_params, onchain_params, read_challenge, read_ptau, srs_fingerprint,
+    verify_srs, verify_srs_against,
+};
 
 #[derive(Debug)]
 pub struct CircuitInfo<C: CurveAffine> {
@@ -31,15 +49,18 @@ pub struct CircuitInfo<C: CurveAffine> {
     pub permutation_columns: Vec<Column>,
     pub lookups: Vec<Lookup<C::Scalar>>,
     pub shuffles: Vec<Shuffle<C::Scalar>>,
+    /// the `srs_fingerprint` of the params the shape was keyed with, if known.
+    /// It is serialized as the last entry of the general info, and omitted when unknown.
+    pub srs_fingerprint: Option<[u8; 32]>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct ColumnQuery {
     pub column: Column,
     pub rotation: Rotation,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Column {
     pub index: u32,
     pub column_type: u8,
@@ -59,12 +80,23 @@ impl From<halo2_proofs::plonk::Column<Any>> for Column {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rotation {
     pub rotation: u32,
     pub next: bool,
 }
 
+impl Rotation {
+    /// signed rotation, as in `halo2_proofs::poly::Rotation`.
+    pub fn value(&self) -> i64 {
+        if self.next {
+            self.rotation as i64
+        } else {
+            -(self.rotation as i64)
+        }
+    }
+}
+
 impl From<halo2_proofs::poly::Rotation> for Rotation {
     fn from(value: halo2_proofs::poly::Rotation) -> Self {
         if value.0.is_negative() {
@@ -172,6 +204,19 @@ impl<F: Field> IndexedExpression<F> {
         }
     }
 
+    /// the degree of the expression, queries have degree 1 and constants and challenges 0.
+    pub fn degree(&self) -> usize {
+        match self {
+            IndexedExpression::ConstantIndex(..) | IndexedExpression::Challenge(_) => 0,
+            IndexedExpression::Fixed(_)
+            | IndexedExpression::Advice(_)
+            | IndexedExpression::Instance(_) => 1,
+            IndexedExpression::Negated(a) | IndexedExpression::Scaled(a, _) => a.degree(),
+            IndexedExpression::Sum(a, b) => a.degree().max(b.degree()),
+            IndexedExpression::Product(a, b) => a.degree() + b.degree(),
+        }
+    }
+
     pub fn identifier(&self) -> String {
         let mut cursor = std::io::Cursor::new(Vec::new());
         self.write_identifier(&mut cursor).unwrap();
@@ -317,6 +362,96 @@ where
     C::ScalarExt: FromUniformBytes<64>,
 {
     let vk = keygen_vk(params, circuit)?;
+    build_circuit_info(params.k() as u8, &vk)
+}
+
+/// Generate the circuit info from a frontend-independent compiled circuit.
+/// This lets other PLONKish frontends target the verifier without going through `Circuit`.
+/// The result is the same as `generate_circuit_info` when the circuit is compiled
+/// the way `keygen_vk` does it, that is, with selector compression enabled.
+pub fn generate_circuit_info_from_compiled<C, P>(
+    params: &P,
+    compiled: &CompiledCircuit<C::Scalar>,
+) -> Result<CircuitInfo<C>, Error>
+where
+    C: CurveAffine,
+    P: Params<C>,
+    C::Scalar: FromUniformBytes<64>,
+{
+    let vk = backend_keygen_vk(params, compiled)?;
+    build_circuit_info(params.k() as u8, &vk)
+}
+
+/// Generate the circuit info from a verifying key generated with `params`,
+/// for callers which already ran `keygen_vk`.
+pub fn generate_circuit_info_from_vk<C, P>(
+    params: &P,
+    vk: &VerifyingKey<C>,
+) -> Result<CircuitInfo<C>, Error>
+where
+    C: CurveAffine,
+    P: Params<C>,
+    C::Scalar: FromUniformBytes<64>,
+{
+    build_circuit_info(params.k() as u8, vk)
+}
+
+/// Same as `generate_circuit_info`, but also returns the source-level names
+/// of gates, lookups, shuffles and columns, see [`CircuitMetadata`].
+pub fn generate_circuit_info_with_metadata<C, P, ConcreteCircuit>(
+    params: &P,
+    circuit: &ConcreteCircuit,
+) -> Result<(CircuitInfo<C>, CircuitMetadata), Error>
+where
+    C: CurveAffine,
+    P: Params<C>,
+    ConcreteCircuit: Circuit<C::Scalar>,
+    C::Scalar: FromUniformBytes<64>,
+{
+    let (compiled, _, _) = compile_circuit(params.k(), circuit, true)?;
+    let info = generate_circuit_info_from_compiled(params, &compiled)?;
+    let metadata = CircuitMetadata::new::<C::Scalar, ConcreteCircuit>(&compiled.cs);
+    Ok((info, metadata))
+}
+
+/// The sha3-256 of the fixed column values and copy constraints of `circuit` at `k`.
+/// The verifying key commits to them, but the shape only holds its gates and columns,
+/// so callers keeping keys around compare this too before reusing them.
+pub fn preprocessing_fingerprint<F, ConcreteCircuit>(
+    k: u32,
+    circuit: &ConcreteCircuit,
+) -> Result<[u8; 32], Error>
+where
+    F: PrimeField,
+    ConcreteCircuit: Circuit<F>,
+{
+    let (compiled, _, _) = compile_circuit(k, circuit, true)?;
+    let preprocessing = &compiled.preprocessing;
+    let mut hasher = Sha3_256::new();
+    // the lengths keep the columns, and the values from the copies, apart.
+    hasher.update((preprocessing.fixed.len() as u64).to_le_bytes());
+    for column in &preprocessing.fixed {
+        hasher.update((column.len() as u64).to_le_bytes());
+        for value in column {
+            hasher.update(PrimeField::to_repr(value));
+        }
+    }
+    let copies = &preprocessing.permutation.copies;
+    hasher.update((copies.len() as u64).to_le_bytes());
+    for cell in copies.iter().flat_map(|(left, right)| [left, right]) {
+        let column = Column::from(halo2_proofs::plonk::Column::<Any>::from(cell.column));
+        hasher.update(column.index.to_le_bytes());
+        hasher.update([column.column_type]);
+        hasher.update((cell.row as u64).to_le_bytes());
+    }
+    Ok(hasher.finalize().into())
+}
+
+fn build_circuit_info<C>(k: u8, vk: &VerifyingKey<C>) -> Result<CircuitInfo<C>, Error>
+where
+    C: CurveAffine,
+    C::Scalar: FromUniformBytes<64>,
+{
     let cs = vk.cs().clone();
 
     let vk_repr = {
@@ -456,7 +591,7 @@ where
         vk_transcript_repr: vk_repr,
         fixed_commitments: vk.fixed_commitments().clone(),
         permutation_commitments: vk.permutation().commitments().to_vec(),
-        k: params.k() as u8,
+        k,
         cs_degree: cs.degree() as u32,
         num_fixed_columns: cs.num_fixed_columns() as u64,
         num_instance_columns: cs.num_instance_columns() as u64,
@@ -496,6 +631,7 @@ where
             .collect(),
         lookups,
         shuffles,
+        srs_fingerprint: None,
         max_num_query_of_advice_column: cs
             .advice_queries()
             .iter()
@@ -520,6 +656,17 @@ fn encode_field<C: CurveAffine>(f: &C::Scalar) -> Vec<u8> {
 }
 
 impl<C: CurveAffine> CircuitInfo<C> {
+    /// sha3-256 of the bcs encoded `serialize` groups, which identifies the published circuit,
+    /// followed by the `srs_fingerprint` if the shape is bound to one.
+    pub fn fingerprint(&self) -> bcs::Result<[u8; 32]> {
+        let mut hasher = Sha3_256::new();
+        hasher.update(bcs::to_bytes(&self.serialize()?)?);
+        if let Some(fingerprint) = self.srs_fingerprint {
+            hasher.update(fingerprint);
+        }
+        Ok(hasher.finalize().into())
+    }
+
     pub fn serialize(&self) -> bcs::Result<Vec<Vec<Vec<u8>>>> {
         let vk_repr = PrimeField::to_repr(&self.vk_transcript_repr)
             .as_ref()
@@ -629,7 +776,7 @@ impl<C: CurveAffine> CircuitInfo<C> {
                 )
             })
             .collect();
-        let result = vec![
+        Ok(vec![
             general_info,
             advice_queries,
             instance_queries,
@@ -641,32 +788,7 @@ impl<C: CurveAffine> CircuitInfo<C> {
             lookups_table_exprs,
             shuffles_input_exprs,
             shuffles_shuffle_exprs,
-        ];
-
-        let item_names = [
-            "General Info",
-            "Advice Queries",
-            "Instance Queries",
-            "Fixed Queries",
-            "Permutation Columns",
-            "Fields Pool",
-            "Gates",
-            "Lookups Input Expressions",
-            "Lookups Table Expressions",
-            "Shuffles Input Expressions",
-            "Shuffles Shuffle Expressions",
-        ];
-
-        for (i, (item, name)) in result.iter().zip(item_names.iter()).enumerate() {
-            let total_size: usize = item.iter().map(|nested| nested.len()).sum();
-            let lengths = item.len();
-            println!(
-                "Item {} ({}): total size = {}, lengths = {:?}",
-                i, name, total_size, lengths
-            );
-        }
-
-        Ok(result)
+        ])
     }
 }
 
@@ -0,0 +1,400 @@
+//! Static checks of a circuit shape before it is published.
+//!
+//! The verifier accepts any shape it can deserialize, so mistakes in the circuit
+//! (unconstrained columns, gates that constrain nothing, a too small k) only
+//! show up once proofs are verified on-chain, or not at all.
+//! `lint` reports them up front, each finding with a severity.
+
+use crate::printer::format_field;
+use crate::{CircuitInfo, Column, ColumnQuery, IndexedExpression};
+use halo2_proofs::arithmetic::{CurveAffine, Field};
+use std::collections::BTreeSet;
+use std::fmt;
+
+const ADVICE: u8 = 1;
+const FIXED: u8 = 2;
+const INSTANCE: u8 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// wasteful, but harmless.
+    Info,
+    /// likely a mistake in the circuit.
+    Warning,
+    /// the shape is unsound or cannot be verified.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    /// short stable identifier of the check, e.g. `unused-advice-column`.
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LintReport {
+    pub findings: Vec<Finding>,
+}
+
+impl LintReport {
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+
+    /// whether any finding is at least as severe as `severity`.
+    pub fn fails_on(&self, severity: Severity) -> bool {
+        self.findings.iter().any(|f| f.severity >= severity)
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    fn push(&mut self, severity: Severity, code: &'static str, message: String) {
+        self.findings.push(Finding {
+            severity,
+            code,
+            message,
+        });
+    }
+}
+
+impl fmt::Display for LintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for finding in &self.findings {
+            writeln!(f, "{}", finding)?;
+        }
+        write!(
+            f,
+            "{} error(s), {} warning(s), {} info(s)",
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+            self.count(Severity::Info)
+        )
+    }
+}
+
+/// lint the shape with the default options, see [`Linter`].
+pub fn lint<C: CurveAffine>(info: &CircuitInfo<C>) -> LintReport {
+    Linter::new(info).run()
+}
+
+pub struct Linter<'a, C: CurveAffine> {
+    info: &'a CircuitInfo<C>,
+    instance_rows: Option<usize>,
+}
+
+impl<'a, C: CurveAffine> Linter<'a, C> {
+    pub fn new(info: &'a CircuitInfo<C>) -> Self {
+        Self {
+            info,
+            instance_rows: None,
+        }
+    }
+
+    /// the number of rows of the largest instance column.
+    /// The shape does not record it, without it only the blinding rows are checked against k.
+    pub fn with_instance_rows(mut self, rows: usize) -> Self {
+        self.instance_rows = Some(rows);
+        self
+    }
+
+    pub fn run(&self) -> LintReport {
+        let mut report = LintReport::default();
+        self.check_columns(&mut report);
+        self.check_gates(&mut report);
+        self.check_degrees(&mut report);
+        self.check_rows(&mut report);
+        self.check_permutation(&mut report);
+        self.check_fields_pool(&mut report);
+        report
+    }
+
+    fn exprs(&self) -> impl Iterator<Item = &IndexedExpression<C::Scalar>> {
+        let info = self.info;
+        info.gates
+            .iter()
+            .flat_map(|g| g.polys.iter())
+            .chain(
+                info.lookups
+                    .iter()
+                    .flat_map(|l| l.input_exprs.iter().chain(&l.table_exprs)),
+            )
+            .chain(
+                info.shuffles
+                    .iter()
+                    .flat_map(|s| s.input_exprs.iter().chain(&s.shuffle_exprs)),
+            )
+    }
+
+    fn check_columns(&self, report: &mut LintReport) {
+        let info = self.info;
+        let queried: BTreeSet<u32> = info.advice_queries.iter().map(|q| q.column.index).collect();
+        for index in 0..info.advice_column_phase.len() as u32 {
+            if !queried.contains(&index) {
+                report.push(
+                    Severity::Warning,
+                    "unused-advice-column",
+                    format!(
+                        "advice[{}] is never queried, its values are unconstrained",
+                        index
+                    ),
+                );
+            }
+        }
+
+        // fixed columns in the permutation hold constants, they are used even without queries.
+        let mut used = BTreeSet::new();
+        for expr in self.exprs() {
+            visit(expr, &mut |e| {
+                if let IndexedExpression::Fixed(index) = e {
+                    if let Some(q) = info.fixed_queries.get(index.value() as usize) {
+                        used.insert(q.column.index);
+                    }
+                }
+            });
+        }
+        for c in &info.permutation_columns {
+            if c.column_type == FIXED {
+                used.insert(c.index);
+            }
+        }
+        for index in 0..info.num_fixed_columns as u32 {
+            if !used.contains(&index) {
+                report.push(
+                    Severity::Warning,
+                    "unused-fixed-column",
+                    format!("fixed[{}] is not used by any expression", index),
+                );
+            }
+        }
+    }
+
+    fn check_gates(&self, report: &mut LintReport) {
+        for (i, gate) in self.info.gates.iter().enumerate() {
+            for poly in &gate.polys {
+                match self.fold(poly) {
+                    Some(v) if v == C::Scalar::ZERO => report.push(
+                        Severity::Warning,
+                        "zero-gate",
+                        format!("gate[{}] is identically zero and constrains nothing", i),
+                    ),
+                    Some(v) => report.push(
+                        Severity::Error,
+                        "unsatisfiable-gate",
+                        format!(
+                            "gate[{}] is the non-zero constant {} and can never be satisfied",
+                            i,
+                            format_field(&v)
+                        ),
+                    ),
+                    None => {}
+                }
+            }
+        }
+    }
+
+    fn check_degrees(&self, report: &mut LintReport) {
+        let info = self.info;
+        let max_degree = |exprs: &[IndexedExpression<C::Scalar>]| {
+            exprs.iter().map(|e| e.degree()).fold(1, usize::max)
+        };
+        for (i, lookup) in info.lookups.iter().enumerate() {
+            let degree = 2 + max_degree(&lookup.input_exprs) + max_degree(&lookup.table_exprs);
+            if degree > info.cs_degree as usize {
+                report.push(
+                    Severity::Error,
+                    "lookup-degree",
+                    format!(
+                        "lookup[{}] requires degree {}, but cs_degree is {}",
+                        i, degree, info.cs_degree
+                    ),
+                );
+            }
+        }
+        for (i, shuffle) in info.shuffles.iter().enumerate() {
+            let degree =
+                2 + max_degree(&shuffle.input_exprs).max(max_degree(&shuffle.shuffle_exprs));
+            if degree > info.cs_degree as usize {
+                report.push(
+                    Severity::Error,
+                    "shuffle-degree",
+                    format!(
+                        "shuffle[{}] requires degree {}, but cs_degree is {}",
+                        i, degree, info.cs_degree
+                    ),
+                );
+            }
+        }
+        for (i, gate) in info.gates.iter().enumerate() {
+            let degree = max_degree(&gate.polys);
+            if degree > info.cs_degree as usize {
+                report.push(
+                    Severity::Error,
+                    "gate-degree",
+                    format!(
+                        "gate[{}] has degree {}, but cs_degree is {}",
+                        i, degree, info.cs_degree
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_rows(&self, report: &mut LintReport) {
+        let info = self.info;
+        // same as `ConstraintSystem::blinding_factors` and `minimum_rows` of halo2.
+        let blinding_factors = (info.max_num_query_of_advice_column as usize).max(3) + 2;
+        let minimum_rows = blinding_factors + 3;
+        let n = 1usize << info.k;
+        if n < minimum_rows {
+            report.push(
+                Severity::Error,
+                "k-too-small",
+                format!(
+                    "k = {} gives {} rows, but {} are needed for the blinding rows",
+                    info.k, n, minimum_rows
+                ),
+            );
+            return;
+        }
+        if let Some(rows) = self.instance_rows {
+            let usable_rows = n - (blinding_factors + 1);
+            if rows > usable_rows {
+                report.push(
+                    Severity::Error,
+                    "k-too-small",
+                    format!(
+                        "k = {} leaves {} usable rows, but the instances need {}",
+                        info.k, usable_rows, rows
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_permutation(&self, report: &mut LintReport) {
+        let info = self.info;
+        for column in &info.permutation_columns {
+            let queries: &[ColumnQuery] = match column.column_type {
+                ADVICE => &info.advice_queries,
+                FIXED => &info.fixed_queries,
+                INSTANCE => &info.instance_queries,
+                _ => &[],
+            };
+            let has_cur = queries
+                .iter()
+                .any(|q| q.column == *column && q.rotation.value() == 0);
+            if !has_cur {
+                report.push(
+                    Severity::Error,
+                    "permutation-query",
+                    format!(
+                        "permutation column {} has no rotation-0 query",
+                        describe(column)
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_fields_pool(&self, report: &mut LintReport) {
+        let mut used = BTreeSet::new();
+        for expr in self.exprs() {
+            visit(expr, &mut |e| match e {
+                IndexedExpression::ConstantIndex(index, _)
+                | IndexedExpression::Scaled(_, index) => {
+                    used.insert(index.value());
+                }
+                _ => {}
+            });
+        }
+        for (i, f) in self.info.fields_pool.iter().enumerate() {
+            if !used.contains(&(i as u32)) {
+                report.push(
+                    Severity::Info,
+                    "unused-constant",
+                    format!("fields_pool[{}] = {} is never used", i, format_field(f)),
+                );
+            }
+        }
+    }
+
+    /// evaluate the expression if it does not depend on any query or challenge.
+    fn fold(&self, expr: &IndexedExpression<C::Scalar>) -> Option<C::Scalar> {
+        let pool = &self.info.fields_pool;
+        match expr {
+            IndexedExpression::ConstantIndex(index, _) => pool.get(index.value() as usize).copied(),
+            IndexedExpression::Fixed(_)
+            | IndexedExpression::Advice(_)
+            | IndexedExpression::Instance(_)
+            | IndexedExpression::Challenge(_) => None,
+            IndexedExpression::Negated(a) => self.fold(a).map(|a| -a),
+            IndexedExpression::Sum(a, b) => {
+                // `a - a` cancels out, whatever `a` is.
+                if let IndexedExpression::Negated(b) = b.as_ref() {
+                    if a.identifier() == b.identifier() {
+                        return Some(C::Scalar::ZERO);
+                    }
+                }
+                Some(self.fold(a)? + self.fold(b)?)
+            }
+            IndexedExpression::Product(a, b) => match (self.fold(a), self.fold(b)) {
+                (Some(a), Some(b)) => Some(a * b),
+                (Some(z), None) | (None, Some(z)) if z == C::Scalar::ZERO => Some(z),
+                _ => None,
+            },
+            IndexedExpression::Scaled(a, index) => {
+                let c = *pool.get(index.value() as usize)?;
+                if c == C::Scalar::ZERO {
+                    return Some(c);
+                }
+                self.fold(a).map(|a| a * c)
+            }
+        }
+    }
+}
+
+fn visit<F: Field>(expr: &IndexedExpression<F>, f: &mut impl FnMut(&IndexedExpression<F>)) {
+    f(expr);
+    match expr {
+        IndexedExpression::Negated(a) | IndexedExpression::Scaled(a, _) => visit(a, f),
+        IndexedExpression::Sum(a, b) | IndexedExpression::Product(a, b) => {
+            visit(a, f);
+            visit(b, f);
+        }
+        _ => {}
+    }
+}
+
+fn describe(column: &Column) -> String {
+    let name = match column.column_type {
+        ADVICE => "advice",
+        FIXED => "fixed",
+        INSTANCE => "instance",
+        _ => "unknown",
+    };
+    format!("{}[{}]", name, column.index)
+}
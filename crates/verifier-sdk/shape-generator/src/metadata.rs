@@ -0,0 +1,181 @@
+//! Source-level names of a circuit, kept next to the shape.
+//!
+//! `CircuitInfo` only carries what the on-chain verifier needs.
+//! Gate, lookup and shuffle names, column annotations and the
+//! selector-to-fixed-column mapping are collected here instead,
+//! so that shape reviews and on-chain failures can be related back to the circuit.
+//! The metadata is serialized to json and never goes on-chain.
+
+use crate::Column;
+use halo2_middleware::circuit::{Any, ConstraintSystemMid, ExpressionMid, VarMid};
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::plonk::{Circuit, ConstraintSystem, Expression};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitMetadata {
+    /// name of each gate polynomial, in the order of `CircuitInfo::gates`.
+    /// halo2 names them `<gate>:<index>` or `<gate>:<index>:<constraint>`.
+    pub gates: Vec<String>,
+    pub lookups: Vec<String>,
+    pub shuffles: Vec<String>,
+    pub column_annotations: Vec<ColumnAnnotation>,
+    pub selectors: Vec<SelectorColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnAnnotation {
+    pub column_type: u8,
+    pub index: u32,
+    pub annotation: String,
+}
+
+/// the fixed column a selector is turned into during keygen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectorColumn {
+    pub selector: usize,
+    pub fixed_column: u32,
+}
+
+impl CircuitMetadata {
+    /// collect the metadata of a circuit compiled into `cs`.
+    pub fn new<F, ConcreteCircuit>(cs: &ConstraintSystemMid<F>) -> Self
+    where
+        F: Field,
+        ConcreteCircuit: Circuit<F>,
+    {
+        let mut column_annotations: Vec<_> = cs
+            .general_column_annotations
+            .iter()
+            .map(|(column, annotation)| {
+                let column: Column = halo2_proofs::plonk::Column::<Any>::from(*column).into();
+                ColumnAnnotation {
+                    column_type: column.column_type,
+                    index: column.index,
+                    annotation: annotation.clone(),
+                }
+            })
+            .collect();
+        column_annotations.sort_by_key(|a| (a.column_type, a.index));
+
+        CircuitMetadata {
+            gates: cs.gates.iter().map(|g| g.name.clone()).collect(),
+            lookups: cs.lookups.iter().map(|l| l.name.clone()).collect(),
+            shuffles: cs.shuffles.iter().map(|s| s.name.clone()).collect(),
+            column_annotations,
+            selectors: selector_columns::<F, ConcreteCircuit>(cs)
+                .into_iter()
+                .map(|(selector, fixed_column)| SelectorColumn {
+                    selector,
+                    fixed_column,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn annotations(&self) -> impl Iterator<Item = (Column, String)> + '_ {
+        self.column_annotations.iter().map(|a| {
+            (
+                Column {
+                    index: a.index,
+                    column_type: a.column_type,
+                },
+                a.annotation.clone(),
+            )
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Selectors are replaced by fixed columns when the circuit is compiled.
+/// The compiled expressions keep the structure of the configured ones,
+/// so walking both side by side tells which fixed column replaces each selector.
+fn selector_columns<F, ConcreteCircuit>(cs_mid: &ConstraintSystemMid<F>) -> BTreeMap<usize, u32>
+where
+    F: Field,
+    ConcreteCircuit: Circuit<F>,
+{
+    let mut cs = ConstraintSystem::<F>::default();
+    let _ = ConcreteCircuit::configure(&mut cs);
+
+    let mut map = BTreeMap::new();
+    let gates = cs.gates().iter().flat_map(|g| g.polynomials().iter());
+    for (expr, expr_mid) in gates.zip(cs_mid.gates.iter().map(|g| &g.poly)) {
+        walk_selectors(expr, expr_mid, &mut map);
+    }
+    for (lookup, lookup_mid) in cs.lookups().iter().zip(&cs_mid.lookups) {
+        let exprs = lookup
+            .input_expressions()
+            .iter()
+            .chain(lookup.table_expressions());
+        let exprs_mid = lookup_mid
+            .input_expressions
+            .iter()
+            .chain(&lookup_mid.table_expressions);
+        for (expr, expr_mid) in exprs.zip(exprs_mid) {
+            walk_selectors(expr, expr_mid, &mut map);
+        }
+    }
+    for (shuffle, shuffle_mid) in cs.shuffles().iter().zip(&cs_mid.shuffles) {
+        let exprs = shuffle
+            .input_expressions()
+            .iter()
+            .chain(shuffle.shuffle_expressions());
+        let exprs_mid = shuffle_mid
+            .input_expressions
+            .iter()
+            .chain(&shuffle_mid.shuffle_expressions);
+        for (expr, expr_mid) in exprs.zip(exprs_mid) {
+            walk_selectors(expr, expr_mid, &mut map);
+        }
+    }
+    map
+}
+
+fn walk_selectors<F: Field>(
+    expr: &Expression<F>,
+    expr_mid: &ExpressionMid<F>,
+    map: &mut BTreeMap<usize, u32>,
+) {
+    match (expr, expr_mid) {
+        (Expression::Selector(selector), _) => {
+            if let Some(column) = first_fixed_column(expr_mid) {
+                map.entry(selector.index()).or_insert(column);
+            }
+        }
+        (Expression::Negated(a), ExpressionMid::Negated(a_mid)) => {
+            walk_selectors(a, a_mid, map);
+        }
+        (Expression::Sum(a, b), ExpressionMid::Sum(a_mid, b_mid))
+        | (Expression::Product(a, b), ExpressionMid::Product(a_mid, b_mid)) => {
+            walk_selectors(a, a_mid, map);
+            walk_selectors(b, b_mid, map);
+        }
+        // a scaled expression is compiled into a product with a constant.
+        (Expression::Scaled(a, _), ExpressionMid::Product(a_mid, _)) => {
+            walk_selectors(a, a_mid, map);
+        }
+        _ => {}
+    }
+}
+
+fn first_fixed_column<F: Field>(expr: &ExpressionMid<F>) -> Option<u32> {
+    match expr {
+        ExpressionMid::Var(VarMid::Query(q)) if q.column_type == Any::Fixed => {
+            Some(q.column_index as u32)
+        }
+        ExpressionMid::Constant(_) | ExpressionMid::Var(_) => None,
+        ExpressionMid::Negated(a) => first_fixed_column(a),
+        ExpressionMid::Sum(a, b) | ExpressionMid::Product(a, b) => {
+            first_fixed_column(a).or_else(|| first_fixed_column(b))
+        }
+    }
+}
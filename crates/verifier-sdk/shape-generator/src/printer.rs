@@ -0,0 +1,406 @@
+//! Human-readable rendering of a circuit shape.
+//!
+//! `IndexedExpression::identifier` only shows pool and query indexes.
+//! The printer resolves them back to columns, rotations and constants,
+//! so that a published shape can be reviewed against the circuit source.
+
+use crate::{CircuitInfo, CircuitMetadata, Column, ColumnQuery, IndexType, IndexedExpression};
+use halo2_proofs::arithmetic::CurveAffine;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// precedence levels used to decide where parentheses are needed.
+const PREC_SUM: u8 = 0;
+const PREC_PRODUCT: u8 = 1;
+const PREC_UNARY: u8 = 2;
+
+pub struct ShapePrinter<'a, C: CurveAffine> {
+    info: &'a CircuitInfo<C>,
+    annotations: HashMap<Column, String>,
+    gate_names: Vec<String>,
+    lookup_names: Vec<String>,
+    shuffle_names: Vec<String>,
+}
+
+impl<'a, C: CurveAffine> ShapePrinter<'a, C> {
+    pub fn new(info: &'a CircuitInfo<C>) -> Self {
+        Self {
+            info,
+            annotations: HashMap::new(),
+            gate_names: vec![],
+            lookup_names: vec![],
+            shuffle_names: vec![],
+        }
+    }
+
+    /// attach column annotations, they are shown next to the column index.
+    pub fn with_annotations(
+        mut self,
+        annotations: impl IntoIterator<Item = (Column, String)>,
+    ) -> Self {
+        self.annotations.extend(annotations);
+        self
+    }
+
+    /// attach the column annotations and the gate, lookup and shuffle names of the circuit.
+    pub fn with_metadata(mut self, metadata: &CircuitMetadata) -> Self {
+        self.gate_names = metadata.gates.clone();
+        self.lookup_names = metadata.lookups.clone();
+        self.shuffle_names = metadata.shuffles.clone();
+        self.with_annotations(metadata.annotations())
+    }
+
+    /// the number of entries and bytes of every group `CircuitInfo::serialize` publishes.
+    pub fn size_report(&self) -> bcs::Result<String> {
+        const GROUPS: [&str; 11] = [
+            "General Info",
+            "Advice Queries",
+            "Instance Queries",
+            "Fixed Queries",
+            "Permutation Columns",
+            "Fields Pool",
+            "Gates",
+            "Lookups Input Expressions",
+            "Lookups Table Expressions",
+            "Shuffles Input Expressions",
+            "Shuffles Shuffle Expressions",
+        ];
+        let mut out = String::new();
+        for (i, (group, name)) in self.info.serialize()?.iter().zip(GROUPS).enumerate() {
+            let total_size: usize = group.iter().map(|bytes| bytes.len()).sum();
+            writeln!(
+                out,
+                "Item {} ({}): total size = {}, lengths = {}",
+                i,
+                name,
+                total_size,
+                group.len()
+            )
+            .unwrap();
+        }
+        Ok(out)
+    }
+
+    /// render the whole shape, one equation per gate, lookup and shuffle.
+    pub fn render(&self) -> String {
+        let info = self.info;
+        let mut out = String::new();
+        writeln!(
+            out,
+            "k = {}, cs_degree = {}, advice columns = {}, fixed columns = {}, instance columns = {}",
+            info.k,
+            info.cs_degree,
+            info.advice_column_phase.len(),
+            info.num_fixed_columns,
+            info.num_instance_columns
+        )
+        .unwrap();
+        if let Some(fingerprint) = info.srs_fingerprint {
+            writeln!(out, "srs = 0x{}", hex::encode(fingerprint)).unwrap();
+        }
+
+        writeln!(out, "gates:").unwrap();
+        for (i, gate) in info.gates.iter().enumerate() {
+            for poly in &gate.polys {
+                writeln!(
+                    out,
+                    "  {}: {} = 0",
+                    label("gate", i, &self.gate_names),
+                    self.expression(poly)
+                )
+                .unwrap();
+            }
+        }
+        writeln!(out, "lookups:").unwrap();
+        for (i, lookup) in info.lookups.iter().enumerate() {
+            writeln!(
+                out,
+                "  {}: {} in {}",
+                label("lookup", i, &self.lookup_names),
+                self.tuple(&lookup.input_exprs),
+                self.tuple(&lookup.table_exprs)
+            )
+            .unwrap();
+        }
+        writeln!(out, "shuffles:").unwrap();
+        for (i, shuffle) in info.shuffles.iter().enumerate() {
+            writeln!(
+                out,
+                "  {}: {} ~ {}",
+                label("shuffle", i, &self.shuffle_names),
+                self.tuple(&shuffle.input_exprs),
+                self.tuple(&shuffle.shuffle_exprs)
+            )
+            .unwrap();
+        }
+        let columns: Vec<_> = info
+            .permutation_columns
+            .iter()
+            .map(|c| self.column(c))
+            .collect();
+        writeln!(out, "permutation: [{}]", columns.join(", ")).unwrap();
+        out
+    }
+
+    /// render a single expression in infix form.
+    pub fn expression(&self, expr: &IndexedExpression<C::Scalar>) -> String {
+        let mut out = String::new();
+        self.write_expression(&mut out, expr, PREC_SUM);
+        out
+    }
+
+    /// export the expression trees of gates, lookups and shuffles as a graphviz digraph.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        let mut next_id = 0usize;
+        out.push_str("digraph shape {\n");
+        out.push_str("  node [fontname=\"monospace\"];\n");
+        for (i, gate) in self.info.gates.iter().enumerate() {
+            self.write_cluster(
+                &mut out,
+                &mut next_id,
+                &label("gate", i, &self.gate_names),
+                &[("", &gate.polys)],
+            );
+        }
+        for (i, lookup) in self.info.lookups.iter().enumerate() {
+            self.write_cluster(
+                &mut out,
+                &mut next_id,
+                &label("lookup", i, &self.lookup_names),
+                &[
+                    ("input", &lookup.input_exprs),
+                    ("table", &lookup.table_exprs),
+                ],
+            );
+        }
+        for (i, shuffle) in self.info.shuffles.iter().enumerate() {
+            self.write_cluster(
+                &mut out,
+                &mut next_id,
+                &label("shuffle", i, &self.shuffle_names),
+                &[
+                    ("input", &shuffle.input_exprs),
+                    ("shuffle", &shuffle.shuffle_exprs),
+                ],
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn write_cluster(
+        &self,
+        out: &mut String,
+        next_id: &mut usize,
+        label: &str,
+        groups: &[(&str, &Vec<IndexedExpression<C::Scalar>>)],
+    ) {
+        let cluster = *next_id;
+        *next_id += 1;
+        writeln!(out, "  subgraph cluster_{} {{", cluster).unwrap();
+        writeln!(out, "    label=\"{}\";", escape(label)).unwrap();
+        for (group, exprs) in groups {
+            for (i, expr) in exprs.iter().enumerate() {
+                let root = self.write_dot_node(out, next_id, expr);
+                let name = if group.is_empty() {
+                    i.to_string()
+                } else {
+                    format!("{}[{}]", group, i)
+                };
+                let id = *next_id;
+                *next_id += 1;
+                writeln!(
+                    out,
+                    "    n{} [label=\"{}\", shape=box, style=dashed];",
+                    id,
+                    escape(&name)
+                )
+                .unwrap();
+                writeln!(out, "    n{} -> n{};", id, root).unwrap();
+            }
+        }
+        out.push_str("  }\n");
+    }
+
+    fn write_dot_node(
+        &self,
+        out: &mut String,
+        next_id: &mut usize,
+        expr: &IndexedExpression<C::Scalar>,
+    ) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        let (label, children): (String, Vec<&IndexedExpression<C::Scalar>>) = match expr {
+            IndexedExpression::Negated(a) => ("-".to_string(), vec![a.as_ref()]),
+            IndexedExpression::Sum(a, b) => ("+".to_string(), vec![a.as_ref(), b.as_ref()]),
+            IndexedExpression::Product(a, b) => ("*".to_string(), vec![a.as_ref(), b.as_ref()]),
+            IndexedExpression::Scaled(a, c) => {
+                (format!("* {}", self.constant(c)), vec![a.as_ref()])
+            }
+            leaf => (self.expression(leaf), vec![]),
+        };
+        let shape = if children.is_empty() {
+            "box"
+        } else {
+            "ellipse"
+        };
+        writeln!(
+            out,
+            "    n{} [label=\"{}\", shape={}];",
+            id,
+            escape(&label),
+            shape
+        )
+        .unwrap();
+        for child in children {
+            let child_id = self.write_dot_node(out, next_id, child);
+            writeln!(out, "    n{} -> n{};", id, child_id).unwrap();
+        }
+        id
+    }
+
+    fn tuple(&self, exprs: &[IndexedExpression<C::Scalar>]) -> String {
+        let exprs: Vec<_> = exprs.iter().map(|e| self.expression(e)).collect();
+        format!("({})", exprs.join(", "))
+    }
+
+    fn write_expression(&self, out: &mut String, expr: &IndexedExpression<C::Scalar>, prec: u8) {
+        match expr {
+            IndexedExpression::ConstantIndex(index, _) => out.push_str(&self.constant(index)),
+            IndexedExpression::Fixed(index) => {
+                out.push_str(&self.query(&self.info.fixed_queries, index, "fixed_query"))
+            }
+            IndexedExpression::Advice(index) => {
+                out.push_str(&self.query(&self.info.advice_queries, index, "advice_query"))
+            }
+            IndexedExpression::Instance(index) => {
+                out.push_str(&self.query(&self.info.instance_queries, index, "instance_query"))
+            }
+            IndexedExpression::Challenge(challenge) => {
+                write!(out, "challenge[{}]", challenge.index).unwrap()
+            }
+            IndexedExpression::Negated(a) => {
+                out.push('-');
+                self.write_expression(out, a, PREC_UNARY);
+            }
+            IndexedExpression::Sum(a, b) => {
+                let paren = prec > PREC_SUM;
+                if paren {
+                    out.push('(');
+                }
+                self.write_expression(out, a, PREC_SUM);
+                if let IndexedExpression::Negated(b) = b.as_ref() {
+                    out.push_str(" - ");
+                    self.write_expression(out, b, PREC_PRODUCT);
+                } else {
+                    out.push_str(" + ");
+                    self.write_expression(out, b, PREC_SUM);
+                }
+                if paren {
+                    out.push(')');
+                }
+            }
+            IndexedExpression::Product(a, b) => {
+                let paren = prec > PREC_PRODUCT;
+                if paren {
+                    out.push('(');
+                }
+                self.write_expression(out, a, PREC_PRODUCT);
+                out.push_str(" * ");
+                self.write_expression(out, b, PREC_PRODUCT);
+                if paren {
+                    out.push(')');
+                }
+            }
+            IndexedExpression::Scaled(a, index) => {
+                let paren = prec > PREC_PRODUCT;
+                if paren {
+                    out.push('(');
+                }
+                out.push_str(&self.constant(index));
+                out.push_str(" * ");
+                self.write_expression(out, a, PREC_PRODUCT);
+                if paren {
+                    out.push(')');
+                }
+            }
+        }
+    }
+
+    fn query(&self, queries: &[ColumnQuery], index: &IndexType, fallback: &str) -> String {
+        match queries.get(index.value() as usize) {
+            Some(q) => format!("{}@{}", self.column(&q.column), q.rotation.value()),
+            None => format!("{}[{}]", fallback, index.value()),
+        }
+    }
+
+    fn column(&self, column: &Column) -> String {
+        let name = match column.column_type {
+            1 => "advice",
+            2 => "fixed",
+            3 => "instance",
+            _ => "unknown",
+        };
+        match self.annotations.get(column) {
+            Some(annotation) => format!("{}[{}:{}]", name, column.index, annotation),
+            None => format!("{}[{}]", name, column.index),
+        }
+    }
+
+    fn constant(&self, index: &IndexType) -> String {
+        match self.info.fields_pool.get(index.value() as usize) {
+            Some(f) => format_field(f),
+            None => format!("constant_index[{}]", index.value()),
+        }
+    }
+}
+
+/// small values (and small negated values) are shown in decimal, others in hex.
+pub fn format_field<F: PrimeField>(f: &F) -> String {
+    if let Some(v) = small_value(f) {
+        return v.to_string();
+    }
+    if let Some(v) = small_value(&-*f) {
+        return format!("-{}", v);
+    }
+    let repr = f.to_repr();
+    let mut bytes = repr.as_ref().to_vec();
+    bytes.reverse();
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn small_value<F: PrimeField>(f: &F) -> Option<u64> {
+    let repr = f.to_repr();
+    let bytes = repr.as_ref();
+    if bytes.len() < 8 || bytes[8..].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let v = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+    // only trust the little-endian guess when it round-trips.
+    (F::from(v) == *f).then_some(v)
+}
+
+fn label(kind: &str, i: usize, names: &[String]) -> String {
+    match names.get(i) {
+        Some(name) if !name.is_empty() => format!("{}[{}] \"{}\"", kind, i, name),
+        _ => format!("{}[{}]", kind, i),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl<C: CurveAffine> CircuitInfo<C> {
+    /// see [`ShapePrinter::render`].
+    pub fn pretty_print(&self) -> String {
+        ShapePrinter::new(self).render()
+    }
+
+    /// see [`ShapePrinter::to_dot`].
+    pub fn to_dot(&self) -> String {
+        ShapePrinter::new(self).to_dot()
+    }
+}
@@ -0,0 +1,374 @@
+//! Identification, import and sanity checks of a KZG setup.
+
+use crate::to_ark::IntoArk;
+use crate::CircuitInfo;
+use halo2_proofs::arithmetic::{best_multiexp, CurveAffine};
+use halo2_proofs::halo2curves::bn256::{Bn256, Fq, Fq2, Fr, G1Affine, G2Affine, G1};
+use halo2_proofs::halo2curves::ff::{Field, PrimeField};
+use halo2_proofs::halo2curves::group::prime::PrimeCurveAffine;
+use halo2_proofs::halo2curves::group::{Curve, Group};
+use halo2_proofs::halo2curves::pairing::Engine;
+use halo2_proofs::plonk::{Error, ErrorFront};
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use rand_core::{OsRng, RngCore};
+use sha3::{Digest, Sha3_256};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// g1, g2 and s_g2 in the arkworks compressed encoding, the arguments of `param_store::create`.
+pub fn onchain_params(params: &ParamsKZG<Bn256>) -> [Vec<u8>; 3] {
+    [
+        params.get_g()[0].to_ark_compressed(),
+        params.g2().to_ark_compressed(),
+        params.s_g2().to_ark_compressed(),
+    ]
+}
+
+/// sha3-256 of the `onchain_params`, in that order.
+/// It does not depend on k, so a downsized setup has the same fingerprint.
+pub fn srs_fingerprint(params: &ParamsKZG<Bn256>) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    for bytes in onchain_params(params) {
+        hasher.update(bytes);
+    }
+    hasher.finalize().into()
+}
+
+impl CircuitInfo<G1Affine> {
+    /// bind the shape to the params it was keyed with, see `srs_fingerprint`.
+    pub fn with_srs_fingerprint(mut self, params: &ParamsKZG<Bn256>) -> Self {
+        self.srs_fingerprint = Some(srs_fingerprint(params));
+        self
+    }
+
+    /// check the shape was keyed with `params`. A shape without a fingerprint is not checked.
+    pub fn check_srs_fingerprint(&self, params: &ParamsKZG<Bn256>) -> Result<(), Error> {
+        match self.srs_fingerprint {
+            Some(fingerprint) if fingerprint != srs_fingerprint(params) => {
+                Err(ErrorFront::Other(format!(
+                    "the shape was keyed with the params 0x{}, not 0x{}",
+                    hex::encode(fingerprint),
+                    hex::encode(srs_fingerprint(params))
+                ))
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Check the params are consistent before publishing them, with fresh randomness.
+/// See `check_params`.
+pub fn verify_srs(params: &ParamsKZG<Bn256>) -> Result<(), Error> {
+    check_params(params, OsRng)
+}
+
+/// `verify_srs`, and check the params are the ones a `param_store` holds on chain,
+/// given as g1, g2 and s_g2 in the encoding of `onchain_params`.
+pub fn verify_srs_against(params: &ParamsKZG<Bn256>, onchain: &[Vec<u8>; 3]) -> Result<(), Error> {
+    verify_srs(params)?;
+    let names = ["g1", "g2", "s_g2"];
+    for ((name, local), onchain) in names.iter().zip(onchain_params(params)).zip(onchain) {
+        if &local != onchain {
+            return Err(ErrorFront::Other(format!(
+                "{} of the params is 0x{}, not 0x{} as on chain",
+                name,
+                hex::encode(local),
+                hex::encode(onchain)
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Consistency checks of a setup:
+/// - g1 and g2 are the standard generators, and every point is in the prime order subgroup.
+/// - `g` are successive powers of the secret in `s_g2`, that is `e(g[i], s_g2) == e(g[i + 1], g2)`.
+///   All the powers are checked at once with a random linear combination drawn from `rng`.
+/// - `g_lagrange` is the lagrange basis of `g`, spot checked by its sum, which is `g[0]`.
+pub fn check_params(params: &ParamsKZG<Bn256>, rng: impl RngCore) -> Result<(), Error> {
+    let fail = |msg: &str| -> Error { ErrorFront::Other(msg.to_string()).into() };
+    let g = params.get_g();
+    let (g2, s_g2) = (params.g2(), params.s_g2());
+    if g.len() < 2 {
+        return Err(fail("the setup has less than two powers of g1"));
+    }
+    if g[0] != G1Affine::generator() {
+        return Err(fail("g1 is not the generator of G1"));
+    }
+    if g2 != G2Affine::generator() || !g2.is_valid_ark() {
+        return Err(fail("g2 is not the generator of G2"));
+    }
+    if !s_g2.is_valid_ark() || s_g2 == G2Affine::identity() || s_g2 == g2 {
+        return Err(fail("s_g2 is not a valid secret point of G2"));
+    }
+    if g.iter().any(|p| !p.is_valid_ark()) {
+        return Err(fail("g contains a point out of the prime order subgroup"));
+    }
+
+    // e(sum r^i * g[i + 1], g2) == e(sum r^i * g[i], s_g2)
+    let r = Fr::random(rng);
+    let coeffs: Vec<Fr> = std::iter::successors(Some(Fr::ONE), |c| Some(*c * r))
+        .take(g.len() - 1)
+        .collect();
+    let shifted = best_multiexp(&coeffs, &g[1..]).to_affine();
+    let base = best_multiexp(&coeffs, &g[..g.len() - 1]).to_affine();
+    if Bn256::pairing(&shifted, &g2) != Bn256::pairing(&base, &s_g2) {
+        return Err(fail("g are not successive powers of the secret in s_g2"));
+    }
+
+    // the lagrange polynomials sum up to one.
+    let g_lagrange = params.get_g_lagrange();
+    let sum = g_lagrange
+        .iter()
+        .fold(G1::identity(), |acc, p| acc + p)
+        .to_affine();
+    if g_lagrange.len() != g.len() || sum != g[0] {
+        return Err(fail("g_lagrange does not match g"));
+    }
+    Ok(())
+}
+
+/// Import the first `2^k` powers from a powers of tau challenge file,
+/// the format of the PSE perpetual powers of tau ceremony.
+///
+/// The file is a 64 bytes hash, followed by `2^(power + 1) - 1` tau powers in G1
+/// and `2^power` tau powers in G2, all points uncompressed with big endian coordinates.
+/// The alpha and beta sections after them are not needed.
+pub fn read_challenge<R: Read>(reader: &mut R, power: u32, k: u32) -> io::Result<ParamsKZG<Bn256>> {
+    const HASH_SIZE: u64 = 64;
+    const G1_SIZE: u64 = 64;
+    if k > power {
+        return Err(invalid_data(format!(
+            "k {} exceeds the power {} of the file",
+            k, power
+        )));
+    }
+    let num_g1 = (1u64 << (power + 1)) - 1;
+    let n = 1u64 << k;
+
+    skip(reader, HASH_SIZE)?;
+    let g = (0..n)
+        .map(|_| read_challenge_g1(reader))
+        .collect::<io::Result<Vec<_>>>()?;
+    skip(reader, (num_g1 - n) * G1_SIZE)?;
+    let g2 = read_challenge_g2(reader)?;
+    let s_g2 = read_challenge_g2(reader)?;
+
+    Ok(params_from_parts(k, g, g2, s_g2))
+}
+
+/// The power of a powers of tau challenge file of `len` bytes.
+///
+/// After the 64 bytes hash, the file holds `2^(power + 1) - 1` tau powers in G1, `2^power` in G2,
+/// `2^power` alpha and beta tau powers in G1 each, and beta in G2,
+/// that is `384 * 2^power + 128` bytes.
+pub fn challenge_power(len: u64) -> io::Result<u32> {
+    let n = len.checked_sub(128).map(|rest| rest / 384);
+    match n {
+        Some(n) if n.is_power_of_two() && len == 384 * n + 128 => Ok(n.trailing_zeros()),
+        _ => Err(invalid_data(format!(
+            "a challenge file of {} bytes is truncated, or not a challenge file",
+            len
+        ))),
+    }
+}
+
+/// Import the first `2^k` powers from a snarkjs `.ptau` file, all of them if `k` is `None`.
+///
+/// The file starts with the `ptau` magic, a version and the number of sections.
+/// Each section is a u32 type and a u64 size followed by its data, all little endian.
+/// The header section holds the base field modulus and the power of the file,
+/// the tau sections hold `2^(power + 1) - 1` points in G1 and `2^power` in G2,
+/// with coordinates in little endian montgomery form.
+pub fn read_ptau<R: Read + Seek>(reader: &mut R, k: Option<u32>) -> io::Result<ParamsKZG<Bn256>> {
+    const HEADER: u32 = 1;
+    const TAU_G1: u32 = 2;
+    const TAU_G2: u32 = 3;
+
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != b"ptau" {
+        return Err(invalid_data("not a ptau file".into()));
+    }
+    let _version = read_u32(reader)?;
+    let num_sections = read_u32(reader)?;
+
+    // the offsets of the sections, which may come in any order.
+    let mut sections = std::collections::HashMap::new();
+    for _ in 0..num_sections {
+        let section_type = read_u32(reader)?;
+        let size = read_u64(reader)?;
+        let offset = reader.stream_position()?;
+        sections.insert(section_type, (offset, size));
+        let size = i64::try_from(size)
+            .map_err(|_| invalid_data(format!("section {} is too large", section_type)))?;
+        reader.seek(SeekFrom::Current(size))?;
+    }
+    let mut seek_section = |reader: &mut R, section_type: u32| -> io::Result<u64> {
+        let (offset, size) = sections
+            .remove(&section_type)
+            .ok_or_else(|| invalid_data(format!("missing section {}", section_type)))?;
+        reader.seek(SeekFrom::Start(offset))?;
+        Ok(size)
+    };
+
+    seek_section(reader, HEADER)?;
+    let n8 = read_u32(reader)?;
+    if n8 != 32 {
+        return Err(invalid_data(format!("unexpected field size {}", n8)));
+    }
+    let mut modulus = [0u8; 32];
+    reader.read_exact(&mut modulus)?;
+    // the modulus is odd, so plus one only touches the lowest byte of `p - 1`.
+    let mut bn254_modulus = (-Fq::ONE).to_repr();
+    bn254_modulus[0] += 1;
+    if modulus != bn254_modulus {
+        return Err(invalid_data("the ptau file is not over bn254".into()));
+    }
+    let power = read_u32(reader)?;
+    let k = k.unwrap_or(power);
+    if k > power {
+        return Err(invalid_data(format!(
+            "k {} exceeds the power {} of the file",
+            k, power
+        )));
+    }
+    // 2^(power + 1) - 1 powers in G1 and 2^power in G2, checked as the power is not trusted.
+    let sizes = power
+        .checked_add(1)
+        .and_then(|p| 1u64.checked_shl(p))
+        .and_then(|n| (n - 1).checked_mul(64))
+        .zip(1u64.checked_shl(power).and_then(|n| n.checked_mul(128)));
+    let Some((g1_min_size, g2_min_size)) = sizes else {
+        return Err(invalid_data(format!("unsupported power {}", power)));
+    };
+
+    let g1_size = seek_section(reader, TAU_G1)?;
+    if g1_size < g1_min_size {
+        return Err(invalid_data("the tauG1 section is too short".into()));
+    }
+    // R^-1, to take the coordinates out of montgomery form.
+    let r_inv = Fq::from(2u64).pow_vartime([256]).invert().unwrap();
+    let g = (0..1u64 << k)
+        .map(|_| read_ptau_g1(reader, r_inv))
+        .collect::<io::Result<Vec<_>>>()?;
+    let g2_size = seek_section(reader, TAU_G2)?;
+    if g2_size < g2_min_size {
+        return Err(invalid_data("the tauG2 section is too short".into()));
+    }
+    let g2 = read_ptau_g2(reader, r_inv)?;
+    let s_g2 = read_ptau_g2(reader, r_inv)?;
+
+    Ok(params_from_parts(k, g, g2, s_g2))
+}
+
+fn params_from_parts(k: u32, g: Vec<G1Affine>, g2: G2Affine, s_g2: G2Affine) -> ParamsKZG<Bn256> {
+    // `from_parts` only reads its arguments, the receiver is a placeholder.
+    let placeholder = ParamsKZG::<Bn256>::unsafe_setup_with_s(0, Fr::ONE);
+    placeholder.from_parts(k, g, None, g2, s_g2)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_ptau_g1<R: Read>(reader: &mut R, r_inv: Fq) -> io::Result<G1Affine> {
+    let x = read_montgomery(reader, r_inv)?;
+    let y = read_montgomery(reader, r_inv)?;
+    if x.is_zero_vartime() && y.is_zero_vartime() {
+        return Ok(G1Affine::identity());
+    }
+    Option::from(G1Affine::from_xy(x, y))
+        .ok_or_else(|| invalid_data("G1 point not on curve".into()))
+}
+
+fn read_ptau_g2<R: Read>(reader: &mut R, r_inv: Fq) -> io::Result<G2Affine> {
+    let x = Fq2::new(
+        read_montgomery(reader, r_inv)?,
+        read_montgomery(reader, r_inv)?,
+    );
+    let y = Fq2::new(
+        read_montgomery(reader, r_inv)?,
+        read_montgomery(reader, r_inv)?,
+    );
+    if x.is_zero_vartime() && y.is_zero_vartime() {
+        return Ok(G2Affine::identity());
+    }
+    Option::from(G2Affine::from_xy(x, y))
+        .ok_or_else(|| invalid_data("G2 point not on curve".into()))
+}
+
+/// a field element `a * R` with `R = 2^256`, in little endian.
+fn read_montgomery<R: Read>(reader: &mut R, r_inv: Fq) -> io::Result<Fq> {
+    let mut repr = <Fq as PrimeField>::Repr::default();
+    reader.read_exact(repr.as_mut())?;
+    let value: Option<Fq> = Fq::from_repr(repr).into();
+    Ok(value.ok_or_else(|| invalid_data("non-canonical field element".into()))? * r_inv)
+}
+
+fn skip<R: Read>(reader: &mut R, len: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(len), &mut io::sink())?;
+    if skipped != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// the infinity flag of the uncompressed encoding, set in the first byte.
+const INFINITY_FLAG: u8 = 0x40;
+/// the compression flag, which challenge files do not set.
+const COMPRESSION_FLAG: u8 = 0x80;
+
+fn read_challenge_g1<R: Read>(reader: &mut R) -> io::Result<G1Affine> {
+    let mut bytes = [0u8; 64];
+    reader.read_exact(&mut bytes)?;
+    if check_flags(bytes[0])? {
+        return Ok(G1Affine::identity());
+    }
+    let x = fq_from_be(&bytes[..32])?;
+    let y = fq_from_be(&bytes[32..])?;
+    Option::from(G1Affine::from_xy(x, y))
+        .ok_or_else(|| invalid_data("G1 point not on curve".into()))
+}
+
+fn read_challenge_g2<R: Read>(reader: &mut R) -> io::Result<G2Affine> {
+    let mut bytes = [0u8; 128];
+    reader.read_exact(&mut bytes)?;
+    if check_flags(bytes[0])? {
+        return Ok(G2Affine::identity());
+    }
+    // the imaginary part comes first.
+    let x = Fq2::new(fq_from_be(&bytes[32..64])?, fq_from_be(&bytes[..32])?);
+    let y = Fq2::new(fq_from_be(&bytes[96..])?, fq_from_be(&bytes[64..96])?);
+    Option::from(G2Affine::from_xy(x, y))
+        .ok_or_else(|| invalid_data("G2 point not on curve".into()))
+}
+
+/// whether the point is the infinity.
+fn check_flags(first: u8) -> io::Result<bool> {
+    if first & COMPRESSION_FLAG != 0 {
+        return Err(invalid_data("expect uncompressed points".into()));
+    }
+    Ok(first & INFINITY_FLAG != 0)
+}
+
+fn fq_from_be(bytes: &[u8]) -> io::Result<Fq> {
+    let mut repr = <Fq as PrimeField>::Repr::default();
+    repr.as_mut().copy_from_slice(bytes);
+    repr.as_mut().reverse();
+    Option::from(Fq::from_repr(repr))
+        .ok_or_else(|| invalid_data("non-canonical field element".into()))
+}
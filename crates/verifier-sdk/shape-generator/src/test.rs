@@ -7,9 +7,167 @@ mod tests {
     use ark_serialize::CanonicalDeserialize;
 
     use group::GroupEncoding;
-    use halo2curves::bn256::G1Affine as Halo2G1Affine;
+    use halo2curves::bn256::{Fr, G1Affine as Halo2G1Affine};
     use hex::{encode, FromHex};
     use std::io::{self};
+    use std::marker::PhantomData;
+
+    use crate::{
+        lint, CircuitInfo, Column, ColumnQuery, Gate, IndexType, IndexedExpression, Linter, Lookup,
+        Rotation, Severity, ShapeBuilder, ShapePrinter,
+    };
+    use halo2curves::ff::PrimeField as _;
+
+    fn query(column_type: u8, index: u32, rotation: i32) -> ColumnQuery {
+        ColumnQuery {
+            column: Column { index, column_type },
+            rotation: Rotation {
+                rotation: rotation.unsigned_abs(),
+                next: rotation >= 0,
+            },
+        }
+    }
+
+    // shape of a vector-mul like circuit:
+    // `fixed[0] * (advice[0] * advice[1] - advice[2])` plus a lookup of `advice[0]@-1` into `3 * fixed[1]`.
+    fn sample_info() -> CircuitInfo<Halo2G1Affine> {
+        use IndexedExpression::*;
+        let gate = Product(
+            Box::new(Fixed(IndexType::U8(0))),
+            Box::new(Sum(
+                Box::new(Product(
+                    Box::new(Advice(IndexType::U8(0))),
+                    Box::new(Advice(IndexType::U8(1))),
+                )),
+                Box::new(Negated(Box::new(Advice(IndexType::U8(2))))),
+            )),
+        );
+        CircuitInfo {
+            vk_transcript_repr: Fr::from(0),
+            fixed_commitments: vec![],
+            permutation_commitments: vec![],
+            k: 4,
+            max_num_query_of_advice_column: 2,
+            cs_degree: 4,
+            num_fixed_columns: 2,
+            num_instance_columns: 0,
+            advice_column_phase: vec![0, 0, 0],
+            challenge_phase: vec![],
+            fields_pool: vec![Fr::from(3)],
+            gates: vec![Gate {
+                polys: vec![gate],
+                _phantom: PhantomData,
+            }],
+            advice_queries: vec![
+                query(1, 0, 0),
+                query(1, 1, 0),
+                query(1, 2, 0),
+                query(1, 0, -1),
+            ],
+            instance_queries: vec![],
+            fixed_queries: vec![query(2, 0, 0), query(2, 1, 0)],
+            permutation_columns: vec![],
+            lookups: vec![Lookup {
+                input_exprs: vec![Advice(IndexType::U8(3))],
+                table_exprs: vec![Scaled(Box::new(Fixed(IndexType::U8(1))), IndexType::U8(0))],
+                _phantom: PhantomData,
+            }],
+            shuffles: vec![],
+            srs_fingerprint: None,
+        }
+    }
+
+    #[test]
+    fn test_pretty_print() {
+        let info = sample_info();
+        let printer = ShapePrinter::new(&info).with_annotations([(
+            Column {
+                index: 2,
+                column_type: 1,
+            },
+            "out".to_string(),
+        )]);
+        let rendered = printer.render();
+        assert!(rendered
+            .contains("gate[0]: fixed[0]@0 * (advice[0]@0 * advice[1]@0 - advice[2:out]@0) = 0"));
+        assert!(rendered.contains("lookup[0]: (advice[0]@-1) in (3 * fixed[1]@0)"));
+
+        let dot = printer.to_dot();
+        assert!(dot.starts_with("digraph shape {"));
+        assert!(dot.contains("label=\"advice[2:out]@0\""));
+        assert!(dot.contains("label=\"* 3\""));
+
+        let sizes = printer.size_report().unwrap();
+        assert_eq!(sizes.lines().count(), 11);
+        assert!(sizes.starts_with("Item 0 (General Info): total size = "));
+        assert!(sizes.contains("Item 6 (Gates): total size = "));
+    }
+
+    #[test]
+    fn test_lint() {
+        let info = sample_info();
+        let report = lint(&info);
+        assert!(report.findings.is_empty(), "{}", report);
+        // 16 rows, of which 5 are blinding rows and 1 is the last row.
+        assert!(!Linter::new(&info).with_instance_rows(10).run().has_errors());
+        assert!(Linter::new(&info).with_instance_rows(11).run().has_errors());
+
+        let mut info = sample_info();
+        info.k = 2;
+        info.num_fixed_columns = 3;
+        info.advice_column_phase.push(0);
+        info.num_instance_columns = 1;
+        info.permutation_columns.push(Column {
+            index: 0,
+            column_type: 3,
+        });
+        info.fields_pool.push(Fr::from(5));
+        info.cs_degree = 3;
+        {
+            use IndexedExpression::*;
+            let a = || Box::new(Advice(IndexType::U8(0)));
+            info.gates.push(Gate {
+                polys: vec![Sum(a(), Box::new(Negated(a())))],
+                _phantom: PhantomData,
+            });
+            info.gates.push(Gate {
+                polys: vec![Scaled(
+                    Box::new(ConstantIndex(IndexType::U8(0), PhantomData)),
+                    IndexType::U8(1),
+                )],
+                _phantom: PhantomData,
+            });
+        }
+
+        let report = lint(&info);
+        let codes: Vec<_> = report
+            .findings
+            .iter()
+            .map(|f| (f.severity, f.code))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                (Severity::Warning, "unused-advice-column"),
+                (Severity::Warning, "unused-fixed-column"),
+                (Severity::Warning, "zero-gate"),
+                (Severity::Error, "unsatisfiable-gate"),
+                (Severity::Error, "lookup-degree"),
+                (Severity::Error, "k-too-small"),
+                (Severity::Error, "permutation-query"),
+            ]
+        );
+        assert!(report.has_errors());
+        assert!(report.fails_on(Severity::Warning));
+
+        // the constant 5 is only used by the unsatisfiable gate.
+        info.gates.pop();
+        let report = lint(&info);
+        assert!(report
+            .findings
+            .iter()
+            .any(|f| f.code == "unused-constant" && f.message.contains("= 5")));
+    }
 
     #[test]
     fn test_arkworks() -> Result<(), Box<dyn std::error::Error>> {
@@ -65,4 +223,93 @@ mod tests {
 
         Ok(())
     }
+
+    // the expected bytes are the vector-mul shape used in `packages/api/tests/verifier_api_test.move`.
+    #[test]
+    fn test_shape_builder_matches_vector_mul() -> Result<(), Box<dyn std::error::Error>> {
+        let point = |h: &[u8]| -> Halo2G1Affine {
+            let bytes = <Vec<u8>>::from_hex(h).unwrap();
+            read(&mut &bytes[..]).unwrap()
+        };
+        let hex_items = |items: &[&str]| -> Vec<Vec<u8>> {
+            items
+                .iter()
+                .map(|h| <Vec<u8>>::from_hex(h).unwrap())
+                .collect()
+        };
+        let vk_repr = "3f8c4673ba5c3eb53ddb38c02cfa47483c4d6a25fbc64ea44c50309fbd413d2c";
+        let fixed_commitment = "a7c40e6e753cfd404ff8e10e1352a3eb77c8e0495bf1d9b7c67410ce4f2a5a98";
+        let permutation_commitments = "f1ad77997188215fb04be428716b389ff29c6ef944e36ab74ffaa9d192d324858d6c5ae20545006f1f4c93446419c848a95dd8e567a2990c1cba488d4a196f0fa9f81ad6c30148acd3011bfd4d6f9a129c577dd059acb81a26d86e7afab696aba66350b2d5606122fa883ea1232fc384ffae8f8e3db990ef760506abf2f5f284";
+
+        let mut builder = ShapeBuilder::<Halo2G1Affine>::new(12);
+        let advice = [
+            builder.advice_column(0),
+            builder.advice_column(0),
+            builder.advice_column(0),
+        ];
+        let instance = builder.instance_column();
+        let s_mul = builder.fixed_column();
+        builder.enable_equality(instance);
+        for column in advice {
+            builder.enable_equality(column);
+        }
+        let lhs = builder.query(advice[0], 0);
+        let rhs = builder.query(advice[1], 0);
+        let out = builder.query(advice[2], 0);
+        let s_mul = builder.query(s_mul, 0);
+        let repr: [u8; 32] = <Vec<u8>>::from_hex(vk_repr)?.try_into().unwrap();
+        builder
+            .gate(vec![s_mul * (lhs * rhs - out)])
+            .fixed_commitments(vec![point(fixed_commitment.as_bytes())])
+            .permutation_commitments(
+                permutation_commitments
+                    .as_bytes()
+                    .chunks(64)
+                    .map(point)
+                    .collect(),
+            )
+            .transcript_repr(Fr::from_repr(repr).unwrap());
+
+        let data = builder.build()?.serialize()?;
+        assert_eq!(
+            data[0],
+            hex_items(&[
+                vk_repr,
+                fixed_commitment,
+                permutation_commitments,
+                "0c",
+                "01000000",
+                "03000000",
+                "0100000000000000",
+                "0100000000000000",
+                "000000",
+                "",
+                "00",
+                "00"
+            ])
+        );
+        assert_eq!(
+            data[1],
+            hex_items(&[
+                "01000000000100000000",
+                "01010000000100000000",
+                "01020000000100000000"
+            ])
+        );
+        assert_eq!(data[2], hex_items(&["03000000000100000000"]));
+        assert_eq!(data[3], hex_items(&["02000000000100000000"]));
+        assert_eq!(
+            data[4],
+            hex_items(&["0300000000", "0100000000", "0101000000", "0102000000"])
+        );
+        assert!(data[5].is_empty());
+        assert_eq!(data[6], hex_items(&["080200070803000301060302"]));
+
+        // querying an undeclared rotation is rejected.
+        let mut builder = ShapeBuilder::<Halo2G1Affine>::new(12);
+        let a = builder.advice_column(0);
+        builder.gate(vec![crate::Expr::Query(a, 1)]);
+        assert!(builder.build().is_err());
+        Ok(())
+    }
 }
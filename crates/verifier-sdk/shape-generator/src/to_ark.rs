@@ -1,6 +1,6 @@
 use ark_ec::short_weierstrass::SWCurveConfig;
 use ark_ec::CurveConfig;
-use ark_serialize::CanonicalDeserialize;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::halo2curves::CurveAffine;
 
@@ -23,6 +23,30 @@ pub trait IntoArk: CurveAffine {
             ark_ec::short_weierstrass::Affine::<Self::ArkConfig>::identity()
         }
     }
+
+    /// the arkworks compressed encoding, which is what aptos `crypto_algebra` deserializes.
+    fn to_ark_compressed(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.to_ark()
+            .serialize_compressed(&mut bytes)
+            .expect("serialize to vec should not fail");
+        bytes
+    }
+
+    /// the arkworks uncompressed encoding, both coordinates in little endian.
+    fn to_ark_uncompressed(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.to_ark()
+            .serialize_uncompressed(&mut bytes)
+            .expect("serialize to vec should not fail");
+        bytes
+    }
+
+    /// whether the point is on the curve and in the prime order subgroup.
+    fn is_valid_ark(&self) -> bool {
+        let point = self.to_ark();
+        point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()
+    }
 }
 
 impl IntoArk for halo2_proofs::halo2curves::bn256::G1Affine {
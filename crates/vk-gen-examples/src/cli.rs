@@ -0,0 +1,812 @@
+//! The vk-gen-examples command line, over the examples of a `Registry`.
+
+use aptos_verifier_api::api::{
+    Deployment, VerifierApi, PUBLISH_CIRCUIT, VERIFIER_MODULE, VERIFY_PROOF,
+};
+use aptos_verifier_api::bundle::ProofBundle;
+use aptos_verifier_api::key_cache::KeyCache;
+use aptos_verifier_api::payload::AccountAddress;
+use aptos_verifier_api::types::EntryFunctionArgumentsJSON;
+use clap::{value_parser, Args, Parser, Subcommand, ValueEnum};
+use shape_generator::to_ark::IntoArk;
+use shape_generator::{
+    challenge_power, read_challenge, read_ptau, srs_fingerprint, verify_srs, verify_srs_against,
+    Linter, Severity, ShapePrinter,
+};
+
+use halo2_proofs::halo2curves::bn256::Bn256;
+use halo2_proofs::poly::commitment::Params;
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use halo2_proofs::SerdeFormat;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::env::current_dir;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::proofs::KzgVariant;
+use crate::registry::{read_witness, Registry};
+
+#[derive(Parser)]
+struct Cli {
+    #[arg(long = "verifier-address", default_value = "0x1")]
+    verifier_address: String,
+    #[arg(long = "verifier-module", default_value = VERIFIER_MODULE)]
+    verifier_module: String,
+    /// the function `build-publish-vk-aptos-txn` calls when the circuit is stored under the sender
+    #[arg(long = "publish-vk-func", default_value = PUBLISH_CIRCUIT)]
+    publish_vk_func: String,
+    /// the function verify payloads call, unless `--mock` is given
+    #[arg(long, default_value = VERIFY_PROOF)]
+    verify_func: String,
+    /// call `mock_verify_proof` instead of `verify_proof`
+    #[arg(long)]
+    mock: bool,
+    /// required by all commands but `params`, `derive-address`, `list-examples` and `build-verify-payload`
+    #[arg(long)]
+    param_path: Option<PathBuf>,
+    #[arg(short)]
+    k: Option<u8>,
+    /// keep the proving and verifying keys of examples in this directory, and reuse them
+    /// while the shape of the example is unchanged
+    #[arg(long)]
+    key_cache: Option<PathBuf>,
+    /// generate the keys again and overwrite the cached ones
+    #[arg(long, requires = "key_cache")]
+    regenerate_keys: bool,
+    /// write payloads as `aptos move run` json files, or as bcs encoded `TransactionPayload`s
+    #[arg(long, value_enum, default_value = "json")]
+    format: PayloadFormat,
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    #[command(flatten)]
+    WithParams(WithParamsCommands),
+    /// build the `verify_proof` payload of a proof bundle, without params
+    BuildVerifyPayload(BuildVerifyPayload),
+    /// print the names of the registered examples
+    ListExamples,
+    /// print the address a circuit or params is deployed at
+    DeriveAddress(DeriveAddress),
+    /// generate, downsize, convert, fingerprint and check params files
+    Params(ParamsCommand),
+}
+
+/// the commands that read the params of `--param-path`.
+#[derive(Subcommand)]
+enum WithParamsCommands {
+    /// print k, g1, g2, s_g2 and the fingerprint of the params
+    ViewParam(ViewParam),
+    BuildCreateParamsAptosTxn(BuildCreateParamsAptosTxn),
+    BuildPublishVkAptosTxn(BuildPublishVkAptosTxn),
+    BuildVerifyProofAptosTxn(BuildVerifyProofTxn),
+    /// prove an example and write the proof bundle, to build its payload or check it elsewhere
+    Prove(Prove),
+    /// check a proof bundle off-chain against an example
+    VerifyLocal(VerifyLocal),
+    PrintShape(PrintShape),
+    Lint(Lint),
+}
+
+#[derive(Parser)]
+struct ParamsCommand {
+    #[command(subcommand)]
+    command: ParamsCommands,
+}
+
+#[derive(Subcommand)]
+enum ParamsCommands {
+    /// generate a setup from a known seed, for tests and local devnets only
+    Gen(GenParams),
+    Downsize(DownsizeParams),
+    Convert(ConvertParams),
+    Fingerprint(ParamsInput),
+    /// check the generators and that g are powers of the secret in s_g2
+    Check(CheckParams),
+}
+
+#[derive(Parser)]
+struct GenParams {
+    #[arg(short, long)]
+    k: u32,
+    /// the secret is derived from the seed, so anyone knowing it can forge proofs
+    #[arg(long)]
+    insecure_seed: u64,
+    #[arg(long = "output", short = 'o')]
+    output: PathBuf,
+    #[arg(long, value_enum, default_value = "raw")]
+    output_format: ParamsOutputFormat,
+}
+
+#[derive(Parser)]
+struct CheckParams {
+    #[command(flatten)]
+    input: ParamsInput,
+    /// also check the params are the ones a `param_store` holds,
+    /// its g1, g2 and s_g2 in hex as `view-param` prints them
+    #[arg(long, num_args = 3, value_names = ["G1", "G2", "S_G2"])]
+    onchain: Option<Vec<String>>,
+    /// also check the params have the `param_store::fingerprint` of the ones on chain, in hex
+    #[arg(long, conflicts_with = "onchain")]
+    onchain_fingerprint: Option<String>,
+}
+
+#[derive(Parser)]
+struct DownsizeParams {
+    #[command(flatten)]
+    input: ParamsInput,
+    #[arg(long = "output", short = 'o')]
+    output: PathBuf,
+    #[arg(long, value_enum, default_value = "raw")]
+    output_format: ParamsOutputFormat,
+}
+
+#[derive(Parser)]
+struct ConvertParams {
+    #[command(flatten)]
+    input: ParamsInput,
+    #[arg(long = "output", short = 'o')]
+    output: PathBuf,
+    #[arg(long, value_enum)]
+    output_format: ParamsOutputFormat,
+}
+
+#[derive(Args)]
+struct ParamsInput {
+    #[arg(long)]
+    input: PathBuf,
+    #[arg(long, value_enum, default_value = "raw")]
+    input_format: ParamsInputFormat,
+    /// the power of a challenge file, it holds 2^power powers of tau.
+    /// it is derived from the file size by default, a ptau file records its own power
+    #[arg(long)]
+    power: Option<u32>,
+    /// keep the first 2^k powers, all of them by default
+    #[arg(short, long)]
+    k: Option<u32>,
+}
+
+/// halo2 `SerdeFormat`s, a powers of tau challenge file, or a snarkjs `.ptau` file.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ParamsInputFormat {
+    Raw,
+    Processed,
+    Challenge,
+    Ptau,
+}
+
+/// a challenge file cannot be written back, as the params lack the powers of tau in G2.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ParamsOutputFormat {
+    Raw,
+    Processed,
+}
+
+#[derive(Parser)]
+struct ViewParam {
+    /// also write the `param_store::create` payload to this directory
+    #[arg(long = "output", short = 'o', value_parser = value_parser ! (PathBuf))]
+    output_dir: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+struct BuildCreateParamsAptosTxn {
+    #[arg(long = "output", short = 'o', value_parser = value_parser ! (PathBuf))]
+    output_dir: Option<PathBuf>,
+    #[command(flatten)]
+    deployment: DeploymentArgs,
+}
+
+#[derive(Parser)]
+struct BuildPublishVkAptosTxn {
+    /// the name of a registered example, see `list-examples`
+    #[arg(long = "example")]
+    example: String,
+    #[arg(long = "output", short = 'o', value_parser = value_parser ! (PathBuf))]
+    output_dir: Option<PathBuf>,
+    #[command(flatten)]
+    deployment: DeploymentArgs,
+    /// call `publish_circuit_with_srs`, so that proofs are only verified against
+    /// params of the fingerprint of `--param-path`
+    #[arg(long)]
+    bind_srs: bool,
+}
+
+/// by default the resource is stored under the sender.
+#[derive(Args)]
+struct DeploymentArgs {
+    /// store it under a named object of the sender, created from the seed
+    #[arg(long, conflicts_with = "resource_account_seed")]
+    object_seed: Option<String>,
+    /// store it under a resource account of the sender, created from the seed
+    #[arg(long)]
+    resource_account_seed: Option<String>,
+}
+
+impl DeploymentArgs {
+    fn deployment(&self) -> Deployment {
+        match (&self.object_seed, &self.resource_account_seed) {
+            (Some(seed), _) => Deployment::Object(seed.as_bytes().to_vec()),
+            (None, Some(seed)) => Deployment::ResourceAccount(seed.as_bytes().to_vec()),
+            (None, None) => Deployment::Sender,
+        }
+    }
+}
+
+/// the address of the published circuit, given or derived as `derive-address` does.
+#[derive(Args)]
+struct CircuitAddressArgs {
+    #[arg(long, required_unless_present = "circuit_deployer")]
+    circuit_address: Option<String>,
+    /// the account that sent `publish_circuit`, with `--object-seed` or `--resource-account-seed`
+    /// if it published the circuit there
+    #[arg(long, conflicts_with = "circuit_address")]
+    circuit_deployer: Option<AccountAddress>,
+    #[command(flatten)]
+    deployment: DeploymentArgs,
+}
+
+impl CircuitAddressArgs {
+    fn address(&self) -> anyhow::Result<String> {
+        match (&self.circuit_address, &self.circuit_deployer) {
+            (_, Some(deployer)) => Ok(self.deployment.deployment().address(deployer).to_string()),
+            (Some(address), None) => {
+                anyhow::ensure!(
+                    self.deployment.deployment() == Deployment::Sender,
+                    "the seed only derives the circuit address from --circuit-deployer"
+                );
+                Ok(address.clone())
+            }
+            (None, None) => anyhow::bail!("--circuit-address or --circuit-deployer is required"),
+        }
+    }
+}
+
+#[derive(Parser)]
+struct DeriveAddress {
+    /// the account sending the create or publish transaction
+    #[arg(long)]
+    deployer: AccountAddress,
+    #[command(flatten)]
+    deployment: DeploymentArgs,
+}
+
+#[derive(Parser)]
+struct PrintShape {
+    /// the name of a registered example, see `list-examples`
+    #[arg(long = "example")]
+    example: String,
+    /// output graphviz dot of the expression trees instead of equations
+    #[arg(long)]
+    dot: bool,
+}
+
+#[derive(Parser)]
+struct Lint {
+    /// the name of a registered example, see `list-examples`
+    #[arg(long = "example")]
+    example: String,
+    /// number of rows of the largest instance column, checked against k
+    #[arg(long)]
+    instance_rows: Option<usize>,
+    /// exit with an error if there are findings of this severity or above
+    #[arg(long, value_enum, default_value = "error")]
+    fail_on: FailOn,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum FailOn {
+    Error,
+    Warning,
+    Info,
+    Never,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum PayloadFormat {
+    Json,
+    Bcs,
+}
+
+#[derive(Parser)]
+struct BuildVerifyProofTxn {
+    /// the name of a registered example, see `list-examples`
+    #[arg(long = "example")]
+    example: String,
+
+    #[arg(long = "output", short = 'o', value_parser = value_parser ! (PathBuf))]
+    output_dir: Option<PathBuf>,
+    #[arg(long)]
+    param_address: String,
+    #[command(flatten)]
+    circuit: CircuitAddressArgs,
+
+    /// gwc or shplonk
+    #[arg(long = "kzg")]
+    variant: KzgVariant,
+    /// prove the private inputs and instances of this JSON or TOML file
+    /// instead of the example's default ones, see the `Witness` type of the example
+    #[arg(long)]
+    witness: Option<PathBuf>,
+    /// seed the randomness of the proof, so that every run gives the same proof bytes,
+    /// for test fixtures. Random by default
+    #[arg(long)]
+    seed: Option<u64>,
+    /// prove this many copies of the witness in one proof, and build a `verify_batch_proof`
+    /// transaction to `<Example>-verify-batch-proof-<kzg>` instead
+    #[arg(long)]
+    batch: Option<usize>,
+}
+
+#[derive(Parser)]
+struct Prove {
+    /// the name of a registered example, see `list-examples`
+    #[arg(long = "example")]
+    example: String,
+    /// the bundle is written to `<example>-<kzg>.bundle.json` in this directory
+    #[arg(long = "output", short = 'o', value_parser = value_parser ! (PathBuf))]
+    output_dir: Option<PathBuf>,
+    /// gwc or shplonk
+    #[arg(long = "kzg")]
+    variant: KzgVariant,
+    /// see `build-verify-proof-aptos-txn`
+    #[arg(long)]
+    witness: Option<PathBuf>,
+    /// see `build-verify-proof-aptos-txn`
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+#[derive(Parser)]
+struct BuildVerifyPayload {
+    /// a bundle written by `prove`
+    #[arg(long)]
+    bundle: PathBuf,
+    #[arg(long = "output", short = 'o', value_parser = value_parser ! (PathBuf))]
+    output_dir: Option<PathBuf>,
+    #[arg(long)]
+    param_address: String,
+    #[command(flatten)]
+    circuit: CircuitAddressArgs,
+}
+
+#[derive(Parser)]
+struct VerifyLocal {
+    /// the name of a registered example, see `list-examples`
+    #[arg(long = "example")]
+    example: String,
+    /// a bundle written by `prove`
+    #[arg(long)]
+    bundle: PathBuf,
+    /// the witness the bundle was proved with, only needed if it changes fixed columns
+    #[arg(long)]
+    witness: Option<PathBuf>,
+}
+
+/// run the command line of the process over the examples of `registry`.
+/// It prints the usage and exits the process on invalid arguments.
+pub fn run(registry: &Registry) -> anyhow::Result<()> {
+    run_cli(registry, Cli::parse())
+}
+
+/// run the command line given by `args`, the first one being the binary name.
+/// Invalid arguments, as well as `--help`, are returned as errors.
+pub fn run_from<I, T>(registry: &Registry, args: I) -> anyhow::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    run_cli(registry, Cli::try_parse_from(args)?)
+}
+
+fn run_cli(registry: &Registry, cli: Cli) -> anyhow::Result<()> {
+    let api = VerifierApi::new(cli.verifier_address)
+        .with_module(cli.verifier_module)
+        .with_publish_circuit_function(cli.publish_vk_func)
+        .with_verify_proof_function(cli.verify_func)
+        .with_mock(cli.mock);
+    let command = match cli.command {
+        Commands::Params(ParamsCommand { command }) => return params_command(command),
+        Commands::ListExamples => {
+            for name in registry.names() {
+                println!("{}", name);
+            }
+            return Ok(());
+        }
+        Commands::DeriveAddress(DeriveAddress {
+            deployer,
+            deployment,
+        }) => {
+            println!("{}", deployment.deployment().address(&deployer));
+            return Ok(());
+        }
+        Commands::BuildVerifyPayload(BuildVerifyPayload {
+            bundle,
+            output_dir,
+            param_address,
+            circuit,
+        }) => {
+            let json =
+                ProofBundle::read(&bundle)?.verify_payload(&api, param_address, circuit.address()?);
+            let output_path = output_dir.unwrap_or_else(|| current_dir().unwrap());
+            std::fs::create_dir_all(output_path.as_path())?;
+
+            let name = bundle
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.trim_end_matches(".bundle.json"))
+                .ok_or_else(|| anyhow::anyhow!("invalid bundle path {}", bundle.display()))?;
+            write_payload(
+                output_path.join(format!("{}-verify-proof", name)),
+                &json,
+                cli.format,
+            )?;
+            return Ok(());
+        }
+        Commands::WithParams(command) => command,
+    };
+
+    let param_path = cli
+        .param_path
+        .ok_or_else(|| anyhow::anyhow!("--param-path is required"))?;
+    let mut param_file = std::fs::File::open(param_path)?;
+
+    let mut params = ParamsKZG::<Bn256>::read(&mut param_file)?;
+    if let Some(k) = cli.k {
+        params.downsize(k as u32);
+    }
+
+    let keys = cli
+        .key_cache
+        .map(|dir| KeyCache::new(dir).with_regenerate(cli.regenerate_keys));
+
+    match command {
+        WithParamsCommands::ViewParam(ViewParam { output_dir }) => {
+            let g1 = params.get_g()[0];
+            let (g2, s_g2) = (params.g2(), params.s_g2());
+            println!("k: {}", params.k());
+            println!("fingerprint: 0x{}", hex::encode(srs_fingerprint(&params)));
+            println!("g1: 0x{}", hex::encode(g1.to_ark_compressed()));
+            println!("g2: 0x{}", hex::encode(g2.to_ark_compressed()));
+            println!("s_g2: 0x{}", hex::encode(s_g2.to_ark_compressed()));
+            println!("uncompressed:");
+            println!("g1: 0x{}", hex::encode(g1.to_ark_uncompressed()));
+            println!("g2: 0x{}", hex::encode(g2.to_ark_uncompressed()));
+            println!("s_g2: 0x{}", hex::encode(s_g2.to_ark_uncompressed()));
+
+            if let Some(output_path) = output_dir {
+                let json = api.create_params(&params)?;
+                std::fs::create_dir_all(output_path.as_path())?;
+                write_payload(
+                    output_path.join(format!("k{}-create-params", params.k())),
+                    &json,
+                    cli.format,
+                )?;
+            }
+        }
+        WithParamsCommands::BuildCreateParamsAptosTxn(BuildCreateParamsAptosTxn {
+            output_dir,
+            deployment,
+        }) => {
+            let json = api.create_params_to(&params, &deployment.deployment())?;
+            let output_path = output_dir.unwrap_or_else(|| current_dir().unwrap());
+            std::fs::create_dir_all(output_path.as_path())?;
+
+            write_payload(
+                output_path.join(format!("k{}-create-params", params.k())),
+                &json,
+                cli.format,
+            )?;
+        }
+        WithParamsCommands::BuildPublishVkAptosTxn(BuildPublishVkAptosTxn {
+            example,
+            output_dir,
+            deployment,
+            bind_srs,
+        }) => {
+            let (circuit_info, metadata) = registry.find(&example)?.circuit_info(&params)?;
+            let json = if bind_srs {
+                api.publish_circuit_with_srs(&params, &circuit_info, &deployment.deployment())?
+            } else {
+                api.publish_circuit_to(&circuit_info, &deployment.deployment())?
+            };
+            print!("{}", ShapePrinter::new(&circuit_info).size_report()?);
+            let output_path = output_dir.unwrap_or_else(|| current_dir().unwrap());
+            std::fs::create_dir_all(output_path.as_path())?;
+
+            write_payload(
+                output_path.join(format!("{}-publish-circuit", file_stem(&example))),
+                &json,
+                cli.format,
+            )?;
+            // the metadata is not part of the payload, it only helps to relate the shape back to the circuit.
+            std::fs::write(
+                output_path.join(format!(
+                    "{}-publish-circuit.metadata.json",
+                    file_stem(&example)
+                )),
+                metadata.to_json()?,
+            )?;
+        }
+        WithParamsCommands::BuildVerifyProofAptosTxn(BuildVerifyProofTxn {
+            example,
+            output_dir,
+            param_address,
+            circuit,
+            variant: kzg,
+            witness,
+            seed,
+            batch,
+        }) => {
+            let circuit_address = circuit.address()?;
+            let witness = witness.as_deref().map(read_witness).transpose()?;
+            let (name, json) = match batch {
+                Some(batch) => {
+                    let (report, instances) = registry.find(&example)?.prove_batch(
+                        &params,
+                        kzg,
+                        witness.as_ref(),
+                        batch,
+                        keys.as_ref(),
+                        &mut proof_rng(seed),
+                    )?;
+                    (
+                        format!("{}-verify-batch-proof-{}", file_stem(&example), kzg),
+                        report.verify_batch_payload(
+                            &api,
+                            param_address,
+                            circuit_address,
+                            &instances,
+                        ),
+                    )
+                }
+                None => {
+                    let bundle = registry.find(&example)?.prove(
+                        &params,
+                        kzg,
+                        witness.as_ref(),
+                        keys.as_ref(),
+                        &mut proof_rng(seed),
+                    )?;
+                    (
+                        format!("{}-verify-proof-{}", file_stem(&example), kzg),
+                        bundle.verify_payload(&api, param_address, circuit_address),
+                    )
+                }
+            };
+
+            let output_path = output_dir.unwrap_or_else(|| current_dir().unwrap());
+            std::fs::create_dir_all(output_path.as_path())?;
+
+            write_payload(output_path.join(name), &json, cli.format)?;
+        }
+        WithParamsCommands::Prove(Prove {
+            example,
+            output_dir,
+            variant: kzg,
+            witness,
+            seed,
+        }) => {
+            let witness = witness.as_deref().map(read_witness).transpose()?;
+            let bundle = registry.find(&example)?.prove(
+                &params,
+                kzg,
+                witness.as_ref(),
+                keys.as_ref(),
+                &mut proof_rng(seed),
+            )?;
+
+            let output_path = output_dir.unwrap_or_else(|| current_dir().unwrap());
+            std::fs::create_dir_all(output_path.as_path())?;
+            bundle.write(&output_path.join(format!("{}-{}.bundle.json", example, kzg)))?;
+        }
+        WithParamsCommands::VerifyLocal(VerifyLocal {
+            example,
+            bundle,
+            witness,
+        }) => {
+            let witness = witness.as_deref().map(read_witness).transpose()?;
+            registry.find(&example)?.verify(
+                &params,
+                &ProofBundle::read(&bundle)?,
+                witness.as_ref(),
+                keys.as_ref(),
+            )?;
+            println!("the proof of {} verifies", example);
+        }
+        WithParamsCommands::PrintShape(PrintShape { example, dot }) => {
+            let (circuit_info, metadata) = registry.find(&example)?.circuit_info(&params)?;
+            let printer = ShapePrinter::new(&circuit_info).with_metadata(&metadata);
+            if dot {
+                print!("{}", printer.to_dot());
+            } else {
+                print!("{}", printer.render());
+            }
+        }
+        WithParamsCommands::Lint(Lint {
+            example,
+            instance_rows,
+            fail_on,
+        }) => {
+            let (circuit_info, _) = registry.find(&example)?.circuit_info(&params)?;
+            let mut linter = Linter::new(&circuit_info);
+            if let Some(rows) = instance_rows {
+                linter = linter.with_instance_rows(rows);
+            }
+            let report = linter.run();
+            println!("{}", report);
+            let severity = match fail_on {
+                FailOn::Error => Some(Severity::Error),
+                FailOn::Warning => Some(Severity::Warning),
+                FailOn::Info => Some(Severity::Info),
+                FailOn::Never => None,
+            };
+            if let Some(severity) = severity {
+                if report.fails_on(severity) {
+                    anyhow::bail!("lint of {} failed", example);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// the name of the payload files of an example, `VectorMul` for `vector-mul`,
+/// as they were named before examples were registered by name.
+fn file_stem(example: &str) -> String {
+    example
+        .split(['-', '_'])
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect()
+}
+
+fn params_command(command: ParamsCommands) -> anyhow::Result<()> {
+    match command {
+        ParamsCommands::Gen(GenParams {
+            k,
+            insecure_seed,
+            output,
+            output_format,
+        }) => {
+            let params = ParamsKZG::<Bn256>::setup(k, StdRng::seed_from_u64(insecure_seed));
+            write_params(output, &params, output_format)?;
+        }
+        ParamsCommands::Downsize(DownsizeParams {
+            input,
+            output,
+            output_format,
+        }) => {
+            anyhow::ensure!(input.k.is_some(), "-k is required to downsize");
+            write_params(output, &read_params(&input)?, output_format)?;
+        }
+        ParamsCommands::Convert(ConvertParams {
+            input,
+            output,
+            output_format,
+        }) => {
+            write_params(output, &read_params(&input)?, output_format)?;
+        }
+        ParamsCommands::Fingerprint(input) => {
+            let params = read_params(&input)?;
+            println!("0x{}", hex::encode(srs_fingerprint(&params)));
+        }
+        ParamsCommands::Check(CheckParams {
+            input,
+            onchain,
+            onchain_fingerprint,
+        }) => {
+            let params = read_params(&input)?;
+            let decode = |s: &str| hex::decode(s.trim_start_matches("0x"));
+            match (onchain, onchain_fingerprint) {
+                (Some(onchain), _) => {
+                    let onchain: [Vec<u8>; 3] = onchain
+                        .iter()
+                        .map(|s| decode(s))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .try_into()
+                        .map_err(|_| anyhow::anyhow!("--onchain takes g1, g2 and s_g2"))?;
+                    verify_srs_against(&params, &onchain)?;
+                }
+                (None, Some(fingerprint)) => {
+                    verify_srs(&params)?;
+                    let local = srs_fingerprint(&params);
+                    anyhow::ensure!(
+                        decode(&fingerprint)? == local,
+                        "the params have the fingerprint 0x{}, not {} as on chain",
+                        hex::encode(local),
+                        fingerprint
+                    );
+                }
+                (None, None) => verify_srs(&params)?,
+            }
+            println!("params of k {} are fine", params.k());
+        }
+    }
+    Ok(())
+}
+
+fn read_params(input: &ParamsInput) -> anyhow::Result<ParamsKZG<Bn256>> {
+    let mut reader = BufReader::new(File::open(&input.input)?);
+    let format = match input.input_format {
+        ParamsInputFormat::Raw => SerdeFormat::RawBytes,
+        ParamsInputFormat::Processed => SerdeFormat::Processed,
+        ParamsInputFormat::Challenge => {
+            // a wrong power would read other points as g2 and s_g2.
+            let power = challenge_power(reader.get_ref().metadata()?.len())?;
+            if let Some(expected) = input.power {
+                anyhow::ensure!(
+                    expected == power,
+                    "{} is a challenge file of power {}, not {}",
+                    input.input.display(),
+                    power,
+                    expected
+                );
+            }
+            let k = input.k.unwrap_or(power);
+            return Ok(read_challenge(&mut reader, power, k)?);
+        }
+        ParamsInputFormat::Ptau => return Ok(read_ptau(&mut reader, input.k)?),
+    };
+    let mut params = ParamsKZG::<Bn256>::read_custom(&mut reader, format)?;
+    if let Some(k) = input.k {
+        anyhow::ensure!(
+            k <= params.k(),
+            "cannot downsize params of k {} to {}",
+            params.k(),
+            k
+        );
+        params.downsize(k);
+    }
+    Ok(params)
+}
+
+fn write_params(
+    path: PathBuf,
+    params: &ParamsKZG<Bn256>,
+    format: ParamsOutputFormat,
+) -> anyhow::Result<()> {
+    let format = match format {
+        ParamsOutputFormat::Raw => SerdeFormat::RawBytes,
+        ParamsOutputFormat::Processed => SerdeFormat::Processed,
+    };
+    let mut writer = BufWriter::new(File::create(&path)?);
+    params.write_custom(&mut writer, format)?;
+    writer.flush()?;
+    println!("params of k {} written to {}", params.k(), path.display());
+    Ok(())
+}
+
+/// the rng of a proof, seeded if `seed` is given.
+fn proof_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// write the payload to `path` with the extension of the format.
+fn write_payload(
+    path: PathBuf,
+    payload: &EntryFunctionArgumentsJSON,
+    format: PayloadFormat,
+) -> anyhow::Result<()> {
+    match format {
+        PayloadFormat::Json => std::fs::write(
+            path.with_extension("json"),
+            serde_json::to_string_pretty(payload)?,
+        )?,
+        PayloadFormat::Bcs => std::fs::write(path.with_extension("bcs"), payload.to_bcs()?)?,
+    }
+    Ok(())
+}
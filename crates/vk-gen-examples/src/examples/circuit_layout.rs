@@ -8,7 +8,11 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
-use rand_core::OsRng;
+use crate::registry::{field_elements, parse_witness, ExampleCircuit, FieldElement};
+use halo2_proofs::halo2curves::bn256::Fr;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Deserialize;
 use std::marker::PhantomData;
 
 /// This represents an advice column at a certain row in the ConstraintSystem
@@ -276,7 +280,8 @@ impl<F: Field> Circuit<F> for MyCircuit<F> {
 pub fn get_example_circuit<F: PrimeField>() -> MyCircuit<F> {
     // Prepare the circuit you want to render.
     // You don't need to include any witness variables.
-    let a = F::random(OsRng);
+    // seeded, so that the table and with it the verifying key are the same on every run.
+    let a = F::random(StdRng::seed_from_u64(0));
     let instance = F::ONE + F::ONE;
     let lookup_table = vec![instance, a, a, F::ZERO];
 
@@ -286,6 +291,45 @@ pub fn get_example_circuit<F: PrimeField>() -> MyCircuit<F> {
     }
 }
 
+/// the witness of `circuit-layout`, it has no instances.
+/// `lookup_table` is fixed, so it changes the shape, and defaults to `[2, a, a, 0]` as in `get_example_circuit`.
+#[derive(Deserialize)]
+pub struct Witness {
+    pub a: FieldElement,
+    pub lookup_table: Option<Vec<FieldElement>>,
+}
+
+/// the `circuit-layout` example of the registry.
+pub struct CircuitLayout;
+
+impl ExampleCircuit for CircuitLayout {
+    type Circuit = MyCircuit<Fr>;
+
+    fn name(&self) -> &str {
+        "circuit-layout"
+    }
+
+    fn circuit(&self) -> (Self::Circuit, Vec<Vec<Fr>>) {
+        (get_example_circuit(), vec![])
+    }
+
+    fn witness_from_json(
+        &self,
+        witness: &serde_json::Value,
+    ) -> anyhow::Result<(Self::Circuit, Vec<Vec<Fr>>)> {
+        let Witness { a, lookup_table } = parse_witness(witness)?;
+        let lookup_table = match lookup_table {
+            Some(table) => field_elements(&table),
+            None => vec![Fr::from(2), a.0, a.0, Fr::ZERO],
+        };
+        let circuit = MyCircuit {
+            a: Value::known(a.0),
+            lookup_table,
+        };
+        Ok((circuit, vec![]))
+    }
+}
+
 // // ANCHOR: dev-graph
 // fn main() {
 //     // Prepare the circuit you want to render.
@@ -1,12 +1,15 @@
 use halo2_proofs::arithmetic::Field;
 
+use crate::registry::{parse_witness, ExampleCircuit, FieldElement};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
     halo2curves::bn256::Fr,
     plonk::{Advice, Circuit, Column, ConstraintSystem, ErrorFront as Error, Fixed, Instance},
     poly::Rotation,
 };
-use rand_core::OsRng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Deserialize;
 
 #[derive(Clone, Copy)]
 pub struct StandardPlonkConfig {
@@ -109,9 +112,42 @@ impl Circuit<Fr> for StandardPlonk {
 }
 
 pub fn get_example_circuit() -> (StandardPlonk, Vec<Fr>) {
-    let c = StandardPlonk(Fr::random(OsRng));
+    // a fixed value rather than a random one, so that `--seed` reproduces the proof.
+    let c = StandardPlonk(Fr::random(StdRng::seed_from_u64(0)));
     (c.clone(), vec![c.0])
 }
+
+/// the witness of `serialization`, `instance` defaults to `value`.
+#[derive(Deserialize)]
+pub struct Witness {
+    pub value: FieldElement,
+    pub instance: Option<FieldElement>,
+}
+
+/// the `serialization` example of the registry.
+pub struct Serialization;
+
+impl ExampleCircuit for Serialization {
+    type Circuit = StandardPlonk;
+
+    fn name(&self) -> &str {
+        "serialization"
+    }
+
+    fn circuit(&self) -> (Self::Circuit, Vec<Vec<Fr>>) {
+        let (circuit, instances) = get_example_circuit();
+        (circuit, vec![instances])
+    }
+
+    fn witness_from_json(
+        &self,
+        witness: &serde_json::Value,
+    ) -> anyhow::Result<(Self::Circuit, Vec<Vec<Fr>>)> {
+        let Witness { value, instance } = parse_witness(witness)?;
+        let instance = instance.unwrap_or(value);
+        Ok((StandardPlonk(value.0), vec![vec![instance.0]]))
+    }
+}
 // fn main() {
 //     let k = 4;
 //     let circuit = StandardPlonk(Fr::random(OsRng));
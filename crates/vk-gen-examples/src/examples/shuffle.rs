@@ -1,3 +1,5 @@
+use crate::registry::{parse_witness, ExampleCircuit, FieldElement};
+use halo2_proofs::halo2curves::bn256::Fr;
 use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::{
     arithmetic::{CurveAffine, Field},
@@ -21,7 +23,10 @@ use halo2_proofs::{
         Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
     },
 };
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use rand_core::{OsRng, RngCore};
+use serde::Deserialize;
 use std::iter;
 
 fn rand_2d_array<F: Field, R: RngCore, const W: usize, const H: usize>(rng: &mut R) -> [[F; H]; W] {
@@ -322,7 +327,57 @@ fn test_prover<C: CurveAffine, const W: usize, const H: usize>(
 }
 
 pub fn get_example_circuit<F: PrimeField>() -> MyCircuit<F, 4, 32> {
-    MyCircuit::<F, 4, 32>::rand(&mut OsRng)
+    // `--seed` only reproduces the proof if the shuffle is the same on every run too.
+    MyCircuit::<F, 4, 32>::rand(&mut StdRng::seed_from_u64(0))
+}
+
+fn to_matrix<const W: usize, const H: usize>(
+    rows: &[Vec<FieldElement>],
+) -> anyhow::Result<[[Fr; H]; W]> {
+    anyhow::ensure!(
+        rows.len() == W && rows.iter().all(|row| row.len() == H),
+        "expected {} columns of {} rows",
+        W,
+        H
+    );
+    Ok(std::array::from_fn(|i| {
+        std::array::from_fn(|j| rows[i][j].0)
+    }))
+}
+
+/// the witness of `shuffle`, 4 columns of 32 rows each, where `shuffled` permutes the rows of `original`.
+/// It has no instances.
+#[derive(Deserialize)]
+pub struct Witness {
+    pub original: Vec<Vec<FieldElement>>,
+    pub shuffled: Vec<Vec<FieldElement>>,
+}
+
+/// the `shuffle` example of the registry.
+pub struct Shuffle;
+
+impl ExampleCircuit for Shuffle {
+    type Circuit = MyCircuit<Fr, 4, 32>;
+
+    fn name(&self) -> &str {
+        "shuffle"
+    }
+
+    fn circuit(&self) -> (Self::Circuit, Vec<Vec<Fr>>) {
+        (get_example_circuit(), vec![])
+    }
+
+    fn witness_from_json(
+        &self,
+        witness: &serde_json::Value,
+    ) -> anyhow::Result<(Self::Circuit, Vec<Vec<Fr>>)> {
+        let Witness { original, shuffled } = parse_witness(witness)?;
+        let circuit = MyCircuit {
+            original: Value::known(to_matrix(&original)?),
+            shuffled: Value::known(to_matrix(&shuffled)?),
+        };
+        Ok((circuit, vec![]))
+    }
 }
 
 // fn main() {
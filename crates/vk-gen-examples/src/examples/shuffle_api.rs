@@ -1,5 +1,7 @@
 use std::marker::PhantomData;
 
+use crate::registry::{field_elements, parse_witness, ExampleCircuit, FieldElement};
+use halo2_proofs::halo2curves::bn256::Fr;
 use halo2_proofs::{
     arithmetic::{CurveAffine, Field},
     circuit::{Layouter, SimpleFloorPlanner, Value},
@@ -22,6 +24,7 @@ use halo2_proofs::{
     },
 };
 use rand_core::OsRng;
+use serde::Deserialize;
 
 struct ShuffleChip<F: Field> {
     config: ShuffleConfig,
@@ -202,6 +205,53 @@ pub fn get_example_circuit<F: PrimeField>() -> MyCircuit<F> {
     }
 }
 
+/// the witness of `shuffle-api`, `(shuffle_0, shuffle_1)` is a permutation of `(input_0, input_1)`.
+/// `input_1` is fixed, so it changes the shape. It has no instances.
+#[derive(Deserialize)]
+pub struct Witness {
+    pub input_0: Vec<FieldElement>,
+    pub input_1: Vec<FieldElement>,
+    pub shuffle_0: Vec<FieldElement>,
+    pub shuffle_1: Vec<FieldElement>,
+}
+
+/// the `shuffle-api` example of the registry.
+pub struct ShuffleApi;
+
+impl ExampleCircuit for ShuffleApi {
+    type Circuit = MyCircuit<Fr>;
+
+    fn name(&self) -> &str {
+        "shuffle-api"
+    }
+
+    fn circuit(&self) -> (Self::Circuit, Vec<Vec<Fr>>) {
+        (get_example_circuit(), vec![])
+    }
+
+    fn witness_from_json(
+        &self,
+        witness: &serde_json::Value,
+    ) -> anyhow::Result<(Self::Circuit, Vec<Vec<Fr>>)> {
+        let witness: Witness = parse_witness(witness)?;
+        let len = witness.input_0.len();
+        anyhow::ensure!(
+            [&witness.input_1, &witness.shuffle_0, &witness.shuffle_1]
+                .iter()
+                .all(|column| column.len() == len),
+            "the inputs and shuffles have different lengths"
+        );
+        let known = |values: &[FieldElement]| values.iter().map(|v| Value::known(v.0)).collect();
+        let circuit = MyCircuit {
+            input_0: known(&witness.input_0),
+            input_1: field_elements(&witness.input_1),
+            shuffle_0: known(&witness.shuffle_0),
+            shuffle_1: known(&witness.shuffle_1),
+        };
+        Ok((circuit, vec![]))
+    }
+}
+
 // #[test]
 // fn test_shuffle_api() {
 //     use halo2_proofs::dev::MockProver;
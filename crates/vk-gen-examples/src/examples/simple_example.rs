@@ -1,5 +1,7 @@
 use std::marker::PhantomData;
 
+use crate::registry::{parse_witness, ExampleCircuit, FieldElement};
+use halo2_proofs::halo2curves::bn256::Fr;
 use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::{
     arithmetic::Field,
@@ -9,6 +11,7 @@ use halo2_proofs::{
     },
     poly::Rotation,
 };
+use serde::Deserialize;
 
 // ANCHOR: instructions
 trait NumericInstructions<F: Field>: Chip<F> {
@@ -324,6 +327,46 @@ pub fn get_example_circuit<F: PrimeField>() -> (MyCircuit<F>, Vec<F>) {
     )
 }
 
+/// the witness of `simple-example`, `c` defaults to `7 * a^2 * b^2`.
+/// The constant 7 is part of the shape.
+#[derive(Deserialize)]
+pub struct Witness {
+    pub a: FieldElement,
+    pub b: FieldElement,
+    pub c: Option<FieldElement>,
+}
+
+/// the `simple-example` example of the registry.
+pub struct SimpleExample;
+
+impl ExampleCircuit for SimpleExample {
+    type Circuit = MyCircuit<Fr>;
+
+    fn name(&self) -> &str {
+        "simple-example"
+    }
+
+    fn circuit(&self) -> (Self::Circuit, Vec<Vec<Fr>>) {
+        let (circuit, instances) = get_example_circuit();
+        (circuit, vec![instances])
+    }
+
+    fn witness_from_json(
+        &self,
+        witness: &serde_json::Value,
+    ) -> anyhow::Result<(Self::Circuit, Vec<Vec<Fr>>)> {
+        let Witness { a, b, c } = parse_witness(witness)?;
+        let constant = Fr::from(7);
+        let c = c.map_or(constant * a.0.square() * b.0.square(), |c| c.0);
+        let circuit = MyCircuit {
+            constant,
+            a: Value::known(a.0),
+            b: Value::known(b.0),
+        };
+        Ok((circuit, vec![vec![c]]))
+    }
+}
+
 // fn main() {
 //     use halo2_proofs::dev::MockProver;
 //     use halo2curves::pasta::Fp;
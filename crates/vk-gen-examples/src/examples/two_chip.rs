@@ -1,5 +1,7 @@
 use std::marker::PhantomData;
 
+use crate::registry::{parse_witness, ExampleCircuit, FieldElement};
+use halo2_proofs::halo2curves::bn256::Fr;
 use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::{
     arithmetic::Field,
@@ -7,7 +9,9 @@ use halo2_proofs::{
     plonk::{Advice, Circuit, Column, ConstraintSystem, ErrorFront as Error, Instance, Selector},
     poly::Rotation,
 };
-use rand_core::OsRng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Deserialize;
 
 // ANCHOR: field-instructions
 /// A variable representing a number.
@@ -496,10 +500,11 @@ impl<F: Field> Circuit<F> for MyCircuit<F> {
 }
 pub fn get_example_circuit<F: PrimeField>() -> (MyCircuit<F>, Vec<F>) {
     // Prepare the private and public inputs to the circuit!
-    let rng = OsRng;
-    let a = F::random(rng);
-    let b = F::random(rng);
-    let c = F::random(rng);
+    // seeded rather than random, as the proofs of `--seed` depend on the witness.
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = F::random(&mut rng);
+    let b = F::random(&mut rng);
+    let c = F::random(&mut rng);
     let d = (a + b) * c;
 
     // Instantiate the circuit with the private inputs.
@@ -514,6 +519,45 @@ pub fn get_example_circuit<F: PrimeField>() -> (MyCircuit<F>, Vec<F>) {
     )
 }
 
+/// the witness of `two-chip`, `d` defaults to `(a + b) * c`.
+#[derive(Deserialize)]
+pub struct Witness {
+    pub a: FieldElement,
+    pub b: FieldElement,
+    pub c: FieldElement,
+    pub d: Option<FieldElement>,
+}
+
+/// the `two-chip` example of the registry.
+pub struct TwoChip;
+
+impl ExampleCircuit for TwoChip {
+    type Circuit = MyCircuit<Fr>;
+
+    fn name(&self) -> &str {
+        "two-chip"
+    }
+
+    fn circuit(&self) -> (Self::Circuit, Vec<Vec<Fr>>) {
+        let (circuit, instances) = get_example_circuit();
+        (circuit, vec![instances])
+    }
+
+    fn witness_from_json(
+        &self,
+        witness: &serde_json::Value,
+    ) -> anyhow::Result<(Self::Circuit, Vec<Vec<Fr>>)> {
+        let Witness { a, b, c, d } = parse_witness(witness)?;
+        let d = d.map_or((a.0 + b.0) * c.0, |d| d.0);
+        let circuit = MyCircuit {
+            a: Value::known(a.0),
+            b: Value::known(b.0),
+            c: Value::known(c.0),
+        };
+        Ok((circuit, vec![vec![d]]))
+    }
+}
+
 // ANCHOR_END: circuit
 
 // #[allow(clippy::many_single_char_names)]
@@ -2,12 +2,15 @@ use std::marker::PhantomData;
 
 use halo2_proofs::halo2curves::ff::PrimeField;
 
+use crate::registry::{field_elements, parse_witness, ExampleCircuit, FieldElement};
+use halo2_proofs::halo2curves::bn256::Fr;
 use halo2_proofs::{
     arithmetic::Field,
     circuit::{AssignedCell, Chip, Layouter, Region, SimpleFloorPlanner, Value},
     plonk::{Advice, Circuit, Column, ConstraintSystem, ErrorFront as Error, Instance, Selector},
     poly::Rotation,
 };
+use serde::Deserialize;
 
 // ANCHOR: instructions
 trait NumericInstructions<F: Field>: Chip<F> {
@@ -288,6 +291,48 @@ pub fn get_example_circuit<F: PrimeField>() -> (MyCircuit<F>, Vec<F>) {
     (circuit, c)
 }
 
+/// the witness of `vector-mul`, three elements per vector, `c` defaults to `a * b` element-wise.
+#[derive(Deserialize)]
+pub struct Witness {
+    pub a: Vec<FieldElement>,
+    pub b: Vec<FieldElement>,
+    pub c: Option<Vec<FieldElement>>,
+}
+
+/// the `vector-mul` example of the registry.
+pub struct VectorMul;
+
+impl ExampleCircuit for VectorMul {
+    type Circuit = MyCircuit<Fr>;
+
+    fn name(&self) -> &str {
+        "vector-mul"
+    }
+
+    fn circuit(&self) -> (Self::Circuit, Vec<Vec<Fr>>) {
+        let (circuit, instances) = get_example_circuit();
+        (circuit, vec![instances])
+    }
+
+    fn witness_from_json(
+        &self,
+        witness: &serde_json::Value,
+    ) -> anyhow::Result<(Self::Circuit, Vec<Vec<Fr>>)> {
+        let Witness { a, b, c } = parse_witness(witness)?;
+        // the length is part of the shape.
+        anyhow::ensure!(a.len() == 3 && b.len() == 3, "a and b must have 3 elements");
+        let c = match c {
+            Some(c) => field_elements(&c),
+            None => a.iter().zip(&b).map(|(a, b)| a.0 * b.0).collect(),
+        };
+        let circuit = MyCircuit {
+            a: a.iter().map(|v| Value::known(v.0)).collect(),
+            b: b.iter().map(|v| Value::known(v.0)).collect(),
+        };
+        Ok((circuit, vec![c]))
+    }
+}
+
 // ANCHOR_END: circuit
 
 // fn main() {
@@ -1,5 +1,9 @@
+pub mod cli;
 pub mod examples;
 
 pub mod proofs;
+pub mod registry;
 
-pub mod to_ark;
+pub use shape_generator::to_ark;
+
+mod test;
@@ -20,32 +20,10 @@ use halo2_proofs::{
     },
     transcript::{Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer},
 };
-use rand::prelude::StdRng;
-use rand::SeedableRng;
-use std::fmt::{Debug, Formatter};
+use rand_core::RngCore;
+use std::fmt::Debug;
 
-#[derive(Copy, Clone, Debug)]
-pub enum KZG {
-    GWC,
-    SHPLONK,
-}
-
-impl KZG {
-    pub fn to_u8(&self) -> u8 {
-        match self {
-            Self::SHPLONK => 0,
-            Self::GWC => 1,
-        }
-    }
-}
-impl std::fmt::Display for KZG {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::GWC => write!(f, "gwc"),
-            Self::SHPLONK => write!(f, "shplonk"),
-        }
-    }
-}
+pub use aptos_verifier_api::kzg::KzgVariant;
 
 /// Proves a circuit using the SHPLONK multi-opening scheme with KZG commitments.
 ///
@@ -54,15 +32,17 @@ impl std::fmt::Display for KZG {
 /// - `instance`: The public inputs for the circuit.
 /// - `params`: The KZG parameters for the curve.
 /// - `pk`: The proving key.
+/// - `rng`: The randomness of the proof, a seeded rng gives the same proof every time.
 ///
 /// # Returns
 /// The proof as a byte vector if successful.
-pub fn prove_circuit<E, ConcreteCircuit>(
+pub fn prove_circuit<E, ConcreteCircuit, R>(
     circuit: ConcreteCircuit,
     instance: &[Vec<E::Fr>],
     params: &ParamsKZG<E>,
     pk: &ProvingKey<E::G1Affine>,
-    kzg: KZG,
+    kzg: KzgVariant,
+    rng: R,
 ) -> Result<Vec<u8>, Error>
 where
     E: Engine + Debug + MultiMillerLoop,
@@ -72,14 +52,17 @@ where
     E::G2Affine: SerdeObject + CurveAffine,
     ConcreteCircuit: Circuit<E::Fr>,
     <E as Engine>::Fr: Ord + WithSmallOrderMulGroup<3> + FromUniformBytes<64>,
+    R: RngCore,
 {
     match kzg {
-        KZG::GWC => prove_circuit_inner::<KZGCommitmentScheme<E>, ProverGWC<E>, _>(
-            circuit, instance, params, pk,
-        ),
-        KZG::SHPLONK => prove_circuit_inner::<KZGCommitmentScheme<E>, ProverSHPLONK<E>, _>(
-            circuit, instance, params, pk,
+        KzgVariant::Gwc => prove_circuit_inner::<KZGCommitmentScheme<E>, ProverGWC<E>, _, _>(
+            circuit, instance, params, pk, rng,
         ),
+        KzgVariant::Shplonk => {
+            prove_circuit_inner::<KZGCommitmentScheme<E>, ProverSHPLONK<E>, _, _>(
+                circuit, instance, params, pk, rng,
+            )
+        }
     }
 }
 fn prove_circuit_inner<
@@ -87,11 +70,13 @@ fn prove_circuit_inner<
     Scheme: CommitmentScheme,
     P: Prover<'params, Scheme>,
     ConcreteCircuit: Circuit<Scheme::Scalar>,
+    R: RngCore,
 >(
     circuit: ConcreteCircuit,
     instance: &[Vec<Scheme::Scalar>],
     params: &'params Scheme::ParamsProver,
     pk: &ProvingKey<Scheme::Curve>,
+    rng: R,
 ) -> Result<Vec<u8>, Error>
 where
     <Scheme as CommitmentScheme>::ParamsVerifier: 'params,
@@ -100,8 +85,6 @@ where
     let mut transcript = Keccak256Write::<Vec<u8>, _, Challenge255<_>>::init(vec![]);
 
     // Create a proof
-    // let rng = StdRng::seed_from_u64(42);
-    let rng = StdRng::from_entropy();
     create_proof::<Scheme, P, _, _, _, _>(
         params,
         pk,
@@ -130,7 +113,7 @@ pub fn verify_circuit<E>(
     params: &ParamsKZG<E>,
     vk: &VerifyingKey<E::G1Affine>,
     proof: &[u8],
-    kzg: KZG,
+    kzg: KzgVariant,
 ) -> Result<(), Error>
 where
     E: Engine + Debug + MultiMillerLoop,
@@ -141,15 +124,12 @@ where
     <E as Engine>::Fr: Ord + WithSmallOrderMulGroup<3> + FromUniformBytes<64>,
 {
     match kzg {
-        KZG::GWC => {
-            verify_circuit_inner::<KZGCommitmentScheme<E>, VerifierGWC<E>, SingleStrategy<E>>(
-                instance,
-                &params.verifier_params(),
-                vk,
-                proof,
-            )
-        }
-        KZG::SHPLONK => verify_circuit_inner::<
+        KzgVariant::Gwc => verify_circuit_inner::<
+            KZGCommitmentScheme<E>,
+            VerifierGWC<E>,
+            SingleStrategy<E>,
+        >(instance, &params.verifier_params(), vk, proof),
+        KzgVariant::Shplonk => verify_circuit_inner::<
             KZGCommitmentScheme<E>,
             VerifierSHPLONK<E>,
             SingleStrategy<E>,
@@ -0,0 +1,315 @@
+//! The examples the CLI knows about, looked up by name.
+//! Downstream crates register their own circuits and run the CLI with `cli::run`.
+
+use crate::examples::{
+    circuit_layout, serialization, shuffle, shuffle_api, simple_example, two_chip, vector_mul,
+};
+use crate::proofs::{prove_circuit, verify_circuit, KzgVariant};
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use aptos_verifier_api::bundle::ProofBundle;
+use aptos_verifier_api::key_cache::KeyCache;
+use aptos_verifier_api::proving::{prove_batch, ProvingReport};
+use halo2_proofs::halo2curves::bn256::{Bn256, Fr, G1Affine};
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::plonk::{keygen_pk, keygen_vk, Circuit};
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use rand_core::RngCore;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use shape_generator::{
+    generate_circuit_info_from_vk, generate_circuit_info_with_metadata, CircuitInfo,
+    CircuitMetadata,
+};
+use std::path::Path;
+
+/// A circuit the CLI can publish, prove and lint.
+pub trait ExampleCircuit {
+    type Circuit: Circuit<Fr>;
+
+    /// the name `--example` selects it by.
+    fn name(&self) -> &str;
+
+    /// the circuit with its default witness, and the instances it is proved with,
+    /// one vector per instance column.
+    fn circuit(&self) -> (Self::Circuit, Vec<Vec<Fr>>);
+
+    /// the circuit and its instances from a JSON witness, for examples that take one.
+    /// Each example documents its schema on its `Witness` type, see `read_witness`.
+    fn witness_from_json(
+        &self,
+        _witness: &serde_json::Value,
+    ) -> Result<(Self::Circuit, Vec<Vec<Fr>>)> {
+        bail!(
+            "example {} does not take a witness",
+            ExampleCircuit::name(self)
+        )
+    }
+}
+
+/// The object safe face of an `ExampleCircuit`, which the registry stores.
+pub trait Example {
+    fn name(&self) -> &str;
+
+    /// the shape of the circuit, bound to the fingerprint of `params`.
+    fn circuit_info(
+        &self,
+        params: &ParamsKZG<Bn256>,
+    ) -> Result<(CircuitInfo<G1Affine>, CircuitMetadata)>;
+
+    /// keygen, prove and check the proof, with the default witness if `witness` is `None`.
+    /// The bundle names the shape bound to `params`, as `build-publish-vk-aptos-txn` publishes it.
+    /// With `keys`, the keys are read from or added to that cache.
+    /// The proof is the same for the same witness, keys and `rng` state.
+    fn prove(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        kzg: KzgVariant,
+        witness: Option<&serde_json::Value>,
+        keys: Option<&KeyCache>,
+        rng: &mut dyn RngCore,
+    ) -> Result<ProofBundle>;
+
+    /// keygen and prove `batch` copies of the circuit of `witness`, or of the default witness,
+    /// in one proof for `verify_batch_proof`, and check it.
+    /// Returns the proof with the instances of each copy.
+    fn prove_batch(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        kzg: KzgVariant,
+        witness: Option<&serde_json::Value>,
+        batch: usize,
+        keys: Option<&KeyCache>,
+        rng: &mut dyn RngCore,
+    ) -> Result<(ProvingReport, Vec<Vec<Vec<Fr>>>)>;
+
+    /// check a bundle off-chain against the circuit of `witness`, or of the default witness.
+    /// Only a witness changing fixed columns changes the circuit.
+    /// With `keys`, the verifying key is read from or added to that cache.
+    fn verify(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        bundle: &ProofBundle,
+        witness: Option<&serde_json::Value>,
+        keys: Option<&KeyCache>,
+    ) -> Result<()>;
+}
+
+impl<E: ExampleCircuit> Example for E {
+    fn name(&self) -> &str {
+        ExampleCircuit::name(self)
+    }
+
+    fn circuit_info(
+        &self,
+        params: &ParamsKZG<Bn256>,
+    ) -> Result<(CircuitInfo<G1Affine>, CircuitMetadata)> {
+        let (circuit, _) = self.circuit();
+        let (info, metadata) = generate_circuit_info_with_metadata(params, &circuit)?;
+        Ok((info.with_srs_fingerprint(params), metadata))
+    }
+
+    fn prove(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        kzg: KzgVariant,
+        witness: Option<&serde_json::Value>,
+        keys: Option<&KeyCache>,
+        rng: &mut dyn RngCore,
+    ) -> Result<ProofBundle> {
+        let (circuit, instances) = match witness {
+            Some(witness) => self.witness_from_json(witness)?,
+            None => self.circuit(),
+        };
+        let pk = match keys {
+            Some(keys) => keys.proving_key(ExampleCircuit::name(self), params, &circuit)?,
+            None => keygen_pk(params, keygen_vk(params, &circuit)?, &circuit)?,
+        };
+        let shape =
+            generate_circuit_info_from_vk(params, pk.get_vk())?.with_srs_fingerprint(params);
+        let proof = prove_circuit(circuit, &instances, params, &pk, kzg, rng)?;
+        verify_circuit(&instances, params, pk.get_vk(), &proof, kzg).map_err(|e| {
+            anyhow!(
+                "the proof of {} does not verify: {:?}",
+                ExampleCircuit::name(self),
+                e
+            )
+        })?;
+        ProofBundle::new(proof, instances, kzg, &shape)
+    }
+
+    fn prove_batch(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        kzg: KzgVariant,
+        witness: Option<&serde_json::Value>,
+        batch: usize,
+        keys: Option<&KeyCache>,
+        rng: &mut dyn RngCore,
+    ) -> Result<(ProvingReport, Vec<Vec<Vec<Fr>>>)> {
+        ensure!(batch > 0, "a batch proves at least one circuit");
+        let (circuits, batch_instances): (Vec<_>, Vec<_>) = (0..batch)
+            .map(|_| match witness {
+                Some(witness) => self.witness_from_json(witness),
+                None => Ok(self.circuit()),
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .map(|(circuit, instances)| (circuit, vec![instances]))
+            .unzip();
+        let pk = match keys {
+            Some(keys) => keys.proving_key(ExampleCircuit::name(self), params, &circuits[0])?,
+            None => keygen_pk(params, keygen_vk(params, &circuits[0])?, &circuits[0])?,
+        };
+        let report = prove_batch(params, &pk, circuits, &batch_instances, kzg, rng, true)
+            .with_context(|| {
+                format!(
+                    "the batch proof of {} does not verify",
+                    ExampleCircuit::name(self)
+                )
+            })?;
+        Ok((report, batch_instances))
+    }
+
+    fn verify(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        bundle: &ProofBundle,
+        witness: Option<&serde_json::Value>,
+        keys: Option<&KeyCache>,
+    ) -> Result<()> {
+        let (circuit, _) = match witness {
+            Some(witness) => self.witness_from_json(witness)?,
+            None => self.circuit(),
+        };
+        let vk = match keys {
+            Some(keys) => keys.verifying_key(ExampleCircuit::name(self), params, &circuit)?,
+            None => keygen_vk(params, &circuit)?,
+        };
+        let shape = generate_circuit_info_from_vk(params, &vk)?.with_srs_fingerprint(params);
+        bundle.verify(params, &vk, &shape)
+    }
+}
+
+/// The examples by name, in registration order.
+#[derive(Default)]
+pub struct Registry {
+    examples: Vec<Box<dyn Example>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the examples of this crate.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register_builtin()
+            .expect("the builtin examples have distinct names");
+        registry
+    }
+
+    fn register_builtin(&mut self) -> Result<()> {
+        self.register(circuit_layout::CircuitLayout)?;
+        self.register(serialization::Serialization)?;
+        self.register(shuffle::Shuffle)?;
+        self.register(shuffle_api::ShuffleApi)?;
+        self.register(simple_example::SimpleExample)?;
+        self.register(two_chip::TwoChip)?;
+        self.register(vector_mul::VectorMul)?;
+        Ok(())
+    }
+
+    /// add an example, its name must not be taken yet.
+    pub fn register<E: ExampleCircuit + 'static>(&mut self, example: E) -> Result<()> {
+        let name = ExampleCircuit::name(&example);
+        ensure!(
+            self.get(name).is_none(),
+            "example {} is already registered",
+            name
+        );
+        self.examples.push(Box::new(example));
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Example> {
+        self.examples
+            .iter()
+            .find(|e| e.name() == name)
+            .map(|e| e.as_ref())
+    }
+
+    /// like `get`, but the error lists the registered examples.
+    pub fn find(&self, name: &str) -> Result<&dyn Example> {
+        self.get(name).ok_or_else(|| {
+            anyhow!(
+                "unknown example {}, expected one of: {}",
+                name,
+                self.names().collect::<Vec<_>>().join(", ")
+            )
+        })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.examples.iter().map(|e| e.name())
+    }
+}
+
+/// A field element of a witness file: a JSON or TOML integer, a decimal string,
+/// or a `0x` prefixed big endian hex string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldElement(pub Fr);
+
+impl<'de> Deserialize<'de> for FieldElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Int(u64),
+            Str(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Int(n) => Ok(FieldElement(Fr::from(n))),
+            Repr::Str(s) => parse_field(&s)
+                .map(FieldElement)
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+fn parse_field(s: &str) -> Result<Fr> {
+    let value = match s.strip_prefix("0x") {
+        Some(hex_str) => {
+            let bytes = hex::decode(format!("{:0>64}", hex_str))?;
+            ensure!(bytes.len() == 32, "{} does not fit in 32 bytes", s);
+            let mut repr = <Fr as PrimeField>::Repr::default();
+            repr.as_mut().copy_from_slice(&bytes);
+            repr.as_mut().reverse();
+            Option::from(Fr::from_repr(repr))
+        }
+        None => Fr::from_str_vartime(s),
+    };
+    value.ok_or_else(|| anyhow!("{} is not a field element", s))
+}
+
+/// the field elements of a witness entry.
+pub fn field_elements(values: &[FieldElement]) -> Vec<Fr> {
+    values.iter().map(|v| v.0).collect()
+}
+
+/// read a witness file, TOML if its extension is `toml` and JSON otherwise.
+pub fn read_witness(path: &Path) -> Result<serde_json::Value> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("cannot read witness {}", path.display()))?;
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        Ok(toml::from_str(&content)?)
+    } else {
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// deserialize the witness of an example into its schema.
+pub fn parse_witness<T: DeserializeOwned>(witness: &serde_json::Value) -> Result<T> {
+    T::deserialize(witness).map_err(|e| anyhow!("invalid witness: {}", e))
+}
@@ -0,0 +1,1213 @@
+#[cfg(test)]
+mod tests {
+    use crate::cli::run_from;
+    use crate::examples::{
+        circuit_layout, serialization, shuffle, shuffle_api, simple_example, two_chip, vector_mul,
+    };
+    use crate::registry::{
+        field_elements, parse_witness, read_witness, ExampleCircuit, FieldElement, Registry,
+    };
+    use aptos_verifier_api::api::VerifierApi;
+    use aptos_verifier_api::bundle::{ProofBundle, TranscriptKind};
+    use aptos_verifier_api::create_params_args;
+    use aptos_verifier_api::key_cache::KeyCache;
+    use aptos_verifier_api::kzg::KzgVariant;
+    use aptos_verifier_api::proving::{
+        keygen_pk, keygen_vk, prove, prove_batch, verify, verify_batch,
+    };
+    use aptos_verifier_api::types::{EntryFunctionArgumentsJSON, MoveArg};
+    use halo2_frontend::circuit::compile_circuit;
+    use halo2_proofs::arithmetic::CurveAffine;
+    use halo2_proofs::halo2curves::bn256::{Bn256, Fq, Fr, G1Affine, G2Affine};
+    use halo2_proofs::halo2curves::ff::{Field, PrimeField};
+    use halo2_proofs::halo2curves::group::prime::PrimeCurveAffine;
+    use halo2_proofs::plonk::{Circuit, VerifyingKey};
+    use halo2_proofs::poly::commitment::Params;
+    use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rand_core::OsRng;
+    use sha3::{Digest, Sha3_256};
+    use shape_generator::to_ark::IntoArk;
+    use shape_generator::{
+        challenge_power, check_params, generate_circuit_info, generate_circuit_info_from_compiled,
+        generate_circuit_info_from_vk, generate_circuit_info_with_metadata, onchain_params,
+        preprocessing_fingerprint, read_challenge, read_ptau, srs_fingerprint, verify_srs,
+        verify_srs_against, CircuitMetadata,
+    };
+    use std::io::Cursor;
+    use std::path::PathBuf;
+
+    const K: u32 = 8;
+
+    /// the builtin registry, params of size `K`, and a scratch directory,
+    /// which is removed when the fixture is dropped, also if an assertion failed first.
+    struct Fixture {
+        registry: Registry,
+        params: ParamsKZG<Bn256>,
+        dir: PathBuf,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("vk-gen-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self {
+                registry: Registry::builtin(),
+                params: ParamsKZG::<Bn256>::setup(K, OsRng),
+                dir,
+            }
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn assert_same_shape<C: Circuit<Fr>>(params: &ParamsKZG<Bn256>, circuit: &C) {
+        let from_circuit = generate_circuit_info(params, circuit).unwrap();
+        let (compiled, _, _) = compile_circuit(params.k(), circuit, true).unwrap();
+        let from_compiled = generate_circuit_info_from_compiled(params, &compiled).unwrap();
+        assert_eq!(
+            from_circuit.serialize().unwrap(),
+            from_compiled.serialize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compiled_circuit_matches_circuit_path() {
+        let params = ParamsKZG::<Bn256>::setup(K, OsRng);
+        assert_same_shape(&params, &circuit_layout::get_example_circuit::<Fr>());
+        assert_same_shape(&params, &serialization::get_example_circuit().0);
+        assert_same_shape(&params, &shuffle::get_example_circuit::<Fr>());
+        assert_same_shape(&params, &shuffle_api::get_example_circuit::<Fr>());
+        assert_same_shape(&params, &simple_example::get_example_circuit::<Fr>().0);
+        assert_same_shape(&params, &two_chip::get_example_circuit::<Fr>().0);
+        assert_same_shape(&params, &vector_mul::get_example_circuit::<Fr>().0);
+    }
+
+    #[test]
+    fn test_circuit_metadata() {
+        let params = ParamsKZG::<Bn256>::setup(K, OsRng);
+        let circuit = vector_mul::get_example_circuit::<Fr>().0;
+        let (info, metadata) = generate_circuit_info_with_metadata(&params, &circuit).unwrap();
+        assert_eq!(
+            info.serialize().unwrap(),
+            generate_circuit_info(&params, &circuit)
+                .unwrap()
+                .serialize()
+                .unwrap()
+        );
+
+        assert_eq!(metadata.gates.len(), info.gates.len());
+        assert!(metadata.gates.iter().all(|name| name.starts_with("mul")));
+        assert!(metadata.lookups.is_empty());
+        assert!(metadata.shuffles.is_empty());
+        // the `s_mul` selector becomes one of the fixed columns.
+        assert_eq!(metadata.selectors.len(), 1);
+        assert_eq!(metadata.selectors[0].selector, 0);
+        assert!((metadata.selectors[0].fixed_column as u64) < info.num_fixed_columns);
+
+        let json = metadata.to_json().unwrap();
+        assert_eq!(CircuitMetadata::from_json(&json).unwrap(), metadata);
+    }
+
+    #[test]
+    fn test_proving_report() {
+        let params = ParamsKZG::<Bn256>::setup(K, OsRng);
+        let (circuit, instances) = vector_mul::get_example_circuit::<Fr>();
+        let instances = vec![instances];
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk.clone(), &circuit).unwrap();
+        let api = VerifierApi::new("0x1");
+
+        for kzg in KzgVariant::ALL {
+            let seeded = || StdRng::seed_from_u64(42);
+            let report = prove(
+                &params,
+                &pk,
+                vector_mul::get_example_circuit::<Fr>().0,
+                &instances,
+                kzg,
+                seeded(),
+                true,
+            )
+            .unwrap();
+            assert_eq!(report.kzg_variant, kzg);
+            assert_eq!(report.proof_size(), report.proof.len());
+            assert!(report.verify_time.is_some());
+            verify(&params, &vk, &instances, &report.proof, kzg).unwrap();
+
+            // the same rng gives the same proof.
+            let again = prove(
+                &params,
+                &pk,
+                vector_mul::get_example_circuit::<Fr>().0,
+                &instances,
+                kzg,
+                seeded(),
+                false,
+            )
+            .unwrap();
+            assert_eq!(again.proof, report.proof);
+            assert!(again.verify_time.is_none());
+
+            let payload =
+                report.verify_payload(&api, "0x1".to_string(), "0x2".to_string(), &instances);
+            assert_eq!(payload.function_id, "0x1::verifier_api::verify_proof");
+            assert_eq!(payload.args[3], MoveArg::Hex(report.proof.clone()));
+            assert_eq!(payload.args[4], MoveArg::U8(kzg.code()));
+        }
+
+        // a proof does not verify with other instances or with the other variant.
+        let report = prove(
+            &params,
+            &pk,
+            circuit,
+            &instances,
+            KzgVariant::Shplonk,
+            OsRng,
+            false,
+        )
+        .unwrap();
+        let wrong = vec![vec![Fr::from(1); instances[0].len()]];
+        assert!(verify(&params, &vk, &wrong, &report.proof, KzgVariant::Shplonk).is_err());
+        assert!(verify(&params, &vk, &instances, &report.proof, KzgVariant::Gwc).is_err());
+    }
+
+    /// the `verify_batch_proof` payload grows slower than the number of circuit instances.
+    /// It says nothing of the gas, dominated by the MSMs and pairings of the verifier,
+    /// measure that on the Move side with `aptos move test --gas`.
+    #[test]
+    fn test_batch_payload_size() {
+        let params = ParamsKZG::<Bn256>::setup(K, OsRng);
+        let (circuit, instances) = vector_mul::get_example_circuit::<Fr>();
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk.clone(), &circuit).unwrap();
+        let api = VerifierApi::new("0x1");
+
+        let mut last_bytes_per_instance = usize::MAX;
+        for n in [1, 2, 4] {
+            let circuits = (0..n)
+                .map(|_| vector_mul::get_example_circuit::<Fr>().0)
+                .collect();
+            let batch = vec![vec![instances.clone()]; n];
+            let report = prove_batch(
+                &params,
+                &pk,
+                circuits,
+                &batch,
+                KzgVariant::Shplonk,
+                OsRng,
+                true,
+            )
+            .unwrap();
+            verify_batch(&params, &vk, &batch, &report.proof, KzgVariant::Shplonk).unwrap();
+            if n > 1 {
+                // the proof does not verify a prefix of the batch.
+                assert!(verify_batch(
+                    &params,
+                    &vk,
+                    &batch[..n - 1],
+                    &report.proof,
+                    KzgVariant::Shplonk
+                )
+                .is_err());
+            }
+
+            let payload =
+                report.verify_batch_payload(&api, "0x1".to_string(), "0x2".to_string(), &batch);
+            assert_eq!(payload.function_id, "0x1::verifier_api::verify_batch_proof");
+            let bytes_per_instance = payload.to_bcs().unwrap().len() / n;
+            assert!(bytes_per_instance < last_bytes_per_instance);
+            last_bytes_per_instance = bytes_per_instance;
+        }
+
+        assert!(prove_batch(
+            &params,
+            &pk,
+            vec![circuit],
+            &[],
+            KzgVariant::Gwc,
+            OsRng,
+            false
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_srs_fingerprint() {
+        let params = ParamsKZG::<Bn256>::setup(K, OsRng);
+        let fingerprint = srs_fingerprint(&params);
+        assert_ne!(
+            fingerprint,
+            srs_fingerprint(&ParamsKZG::<Bn256>::setup(K, OsRng))
+        );
+
+        // downsizing keeps g[0], g2 and s_g2.
+        let mut downsized = params.clone();
+        downsized.downsize(K - 2);
+        assert_eq!(srs_fingerprint(&downsized), fingerprint);
+
+        let g1 = params.get_g()[0];
+        assert_eq!(g1.to_ark_compressed().len(), 32);
+        assert_eq!(g1.to_ark_uncompressed().len(), 64);
+        assert_eq!(params.s_g2().to_ark_compressed().len(), 64);
+        assert_eq!(params.s_g2().to_ark_uncompressed().len(), 128);
+        // the x coordinate comes first in both encodings, the compressed one flags the sign of y.
+        assert_eq!(g1.to_ark_compressed()[..31], g1.to_ark_uncompressed()[..31]);
+    }
+
+    /// the uncompressed big endian encoding of challenge files.
+    fn challenge_point<C: CurveAffine>(point: &C, size: usize) -> Vec<u8>
+    where
+        C::Base: PrimeField<Repr = [u8; 32]>,
+    {
+        let coordinates: Option<_> = point.coordinates().into();
+        match coordinates {
+            Some(c) => [c.x(), c.y()]
+                .iter()
+                .flat_map(|f| f.to_repr().into_iter().rev())
+                .collect(),
+            None => {
+                let mut bytes = vec![0u8; size];
+                bytes[0] = 0x40;
+                bytes
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_challenge() {
+        let power = 4;
+        let params = ParamsKZG::<Bn256>::setup(power, OsRng);
+        let g2_point = |p: &G2Affine| {
+            let c = p.coordinates().unwrap();
+            [c.x().c1, c.x().c0, c.y().c1, c.y().c0]
+                .iter()
+                .flat_map(|f| f.to_repr().into_iter().rev())
+                .collect::<Vec<u8>>()
+        };
+
+        // the hash, 2^(power + 1) - 1 powers in G1 and 2^power in G2, only the first two are read,
+        // then the alpha and beta sections.
+        let mut file = vec![0u8; 64];
+        let g1_len = (1usize << (power + 1)) - 1;
+        for i in 0..g1_len {
+            let point = params
+                .get_g()
+                .get(i)
+                .copied()
+                .unwrap_or(G1Affine::identity());
+            file.extend(challenge_point(&point, 64));
+        }
+        file.extend(g2_point(&params.g2()));
+        file.extend(g2_point(&params.s_g2()));
+        file.extend(vec![0u8; 128 * ((1 << power) - 2)]);
+        file.extend(vec![0u8; 2 * 64 * (1 << power) + 128]);
+        assert_eq!(challenge_power(file.len() as u64).unwrap(), power);
+        assert!(challenge_power(file.len() as u64 - 128).is_err());
+        assert!(challenge_power(file.len() as u64 + 384).is_err());
+        assert!(challenge_power(0).is_err());
+        // the challenge file of the tutorial, of power 28.
+        assert_eq!(challenge_power(103_079_215_232).unwrap(), 28);
+
+        let imported = read_challenge(&mut file.as_slice(), power, power).unwrap();
+        assert_eq!(imported.get_g(), params.get_g());
+        assert_eq!(imported.g2(), params.g2());
+        assert_eq!(imported.s_g2(), params.s_g2());
+        assert_eq!(srs_fingerprint(&imported), srs_fingerprint(&params));
+        check_params(&imported, OsRng).unwrap();
+
+        let smaller = read_challenge(&mut file.as_slice(), power, 2).unwrap();
+        assert_eq!(smaller.k(), 2);
+        assert_eq!(smaller.get_g(), &params.get_g()[..4]);
+
+        assert!(read_challenge(&mut file.as_slice(), power, power + 1).is_err());
+        assert!(read_challenge(&mut &file[..100], power, 2).is_err());
+        let mut compressed = file.clone();
+        compressed[64] |= 0x80;
+        assert!(read_challenge(&mut compressed.as_slice(), power, 2).is_err());
+    }
+
+    #[test]
+    fn test_check_params() {
+        let params = ParamsKZG::<Bn256>::setup(4, OsRng);
+        check_params(&params, OsRng).unwrap();
+
+        let other = ParamsKZG::<Bn256>::setup(4, OsRng);
+        let mixed = params.from_parts(
+            params.k(),
+            params.get_g().to_vec(),
+            None,
+            params.g2(),
+            other.s_g2(),
+        );
+        assert!(check_params(&mixed, OsRng).is_err());
+
+        // a single wrong power is caught.
+        let mut g = params.get_g().to_vec();
+        g[5] = other.get_g()[5];
+        let tampered = params.from_parts(params.k(), g, None, params.g2(), params.s_g2());
+        assert!(check_params(&tampered, OsRng).is_err());
+
+        // a known seed gives the same setup, as `params gen` relies on.
+        let seeded = |seed| ParamsKZG::<Bn256>::setup(4, StdRng::seed_from_u64(seed));
+        assert_eq!(srs_fingerprint(&seeded(1)), srs_fingerprint(&seeded(1)));
+        assert_ne!(srs_fingerprint(&seeded(1)), srs_fingerprint(&seeded(2)));
+    }
+
+    #[test]
+    fn test_verify_srs() {
+        let params = ParamsKZG::<Bn256>::setup(4, OsRng);
+        verify_srs(&params).unwrap();
+        create_params_args(&params).unwrap();
+
+        // a lagrange basis of another setup is caught.
+        let other = ParamsKZG::<Bn256>::setup(4, OsRng);
+        let wrong_lagrange = params.from_parts(
+            params.k(),
+            params.get_g().to_vec(),
+            Some(other.get_g_lagrange().to_vec()),
+            params.g2(),
+            params.s_g2(),
+        );
+        assert!(verify_srs(&wrong_lagrange).is_err());
+        assert!(create_params_args(&wrong_lagrange).is_err());
+
+        // s_g2 must be a secret point, not the generator itself.
+        let degenerate = params.from_parts(
+            params.k(),
+            params.get_g().to_vec(),
+            None,
+            params.g2(),
+            params.g2(),
+        );
+        assert!(verify_srs(&degenerate).is_err());
+    }
+
+    #[test]
+    fn test_verify_srs_against() {
+        let Fixture { registry, dir, .. } = &Fixture::new("check-params");
+        let params = ParamsKZG::<Bn256>::setup(4, StdRng::seed_from_u64(1));
+        let onchain = onchain_params(&params);
+        verify_srs_against(&params, &onchain).unwrap();
+        // params of another setup are fine, but not the ones on chain.
+        let other = ParamsKZG::<Bn256>::setup(4, StdRng::seed_from_u64(2));
+        assert!(verify_srs_against(&other, &onchain).is_err());
+        let mut swapped = onchain.clone();
+        swapped.swap(1, 2);
+        assert!(verify_srs_against(&params, &swapped).is_err());
+
+        let path = dir.join("seeded.srs");
+        let path = path.to_str().unwrap();
+        let gen = [
+            "params",
+            "gen",
+            "-k",
+            "4",
+            "--insecure-seed",
+            "1",
+            "-o",
+            path,
+        ];
+        run_from(registry, ["vk-gen-examples"].into_iter().chain(gen)).unwrap();
+        let check = |args: &[String]| {
+            let command = ["vk-gen-examples", "params", "check", "--input", path];
+            run_from(
+                registry,
+                command.into_iter().map(String::from).chain(args.to_vec()),
+            )
+        };
+        let to_hex = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+        let args = |name: &str, values: &[Vec<u8>]| {
+            [name.to_string()]
+                .into_iter()
+                .chain(values.iter().map(|v| to_hex(v)))
+                .collect::<Vec<_>>()
+        };
+        check(&[]).unwrap();
+        check(&args("--onchain", &onchain)).unwrap();
+        check(&args("--onchain", &onchain_params(&other))).unwrap_err();
+        check(&args("--onchain", &onchain[..2])).unwrap_err();
+        check(&args(
+            "--onchain-fingerprint",
+            &[srs_fingerprint(&params).to_vec()],
+        ))
+        .unwrap();
+        check(&args(
+            "--onchain-fingerprint",
+            &[srs_fingerprint(&other).to_vec()],
+        ))
+        .unwrap_err();
+    }
+
+    /// a snarkjs `.ptau` file of the setup: header, tauG1 and tauG2 sections,
+    /// the coordinates in little endian montgomery form.
+    fn ptau_fixture(params: &ParamsKZG<Bn256>, power: u32) -> Vec<u8> {
+        let montgomery_r = Fq::from(2u64).pow_vartime([256]);
+        let fq = |f: &Fq| (*f * montgomery_r).to_repr().to_vec();
+        let g1 = |p: &G1Affine| -> Vec<u8> {
+            let c: Option<_> = p.coordinates().into();
+            c.map_or(vec![0u8; 64], |c| [fq(c.x()), fq(c.y())].concat())
+        };
+        let g2 = |p: &G2Affine| -> Vec<u8> {
+            let c = p.coordinates().unwrap();
+            [fq(&c.x().c0), fq(&c.x().c1), fq(&c.y().c0), fq(&c.y().c1)].concat()
+        };
+        let section = |section_type: u32, data: Vec<u8>| -> Vec<u8> {
+            [
+                section_type.to_le_bytes().to_vec(),
+                (data.len() as u64).to_le_bytes().to_vec(),
+                data,
+            ]
+            .concat()
+        };
+
+        let mut modulus = (-Fq::ONE).to_repr();
+        modulus[0] += 1;
+        let header = [
+            32u32.to_le_bytes().to_vec(),
+            modulus.to_vec(),
+            power.to_le_bytes().to_vec(),
+            power.to_le_bytes().to_vec(),
+        ]
+        .concat();
+        let tau_g1 = (0..(1usize << (power + 1)) - 1)
+            .flat_map(|i| {
+                g1(&params
+                    .get_g()
+                    .get(i)
+                    .copied()
+                    .unwrap_or(G1Affine::identity()))
+            })
+            .collect();
+        let tau_g2 = [g2(&params.g2()), g2(&params.s_g2())]
+            .concat()
+            .into_iter()
+            .chain(std::iter::repeat(0u8).take(128 * ((1 << power) - 2)))
+            .collect();
+
+        // the sections do not have to be in order.
+        [
+            b"ptau".to_vec(),
+            1u32.to_le_bytes().to_vec(),
+            3u32.to_le_bytes().to_vec(),
+            section(3, tau_g2),
+            section(1, header),
+            section(2, tau_g1),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_read_ptau() {
+        let power = 4;
+        let params = ParamsKZG::<Bn256>::setup(power, OsRng);
+        let file = ptau_fixture(&params, power);
+
+        let imported = read_ptau(&mut Cursor::new(&file), None).unwrap();
+        assert_eq!(imported.k(), power);
+        assert_eq!(imported.get_g(), params.get_g());
+        assert_eq!(imported.g2(), params.g2());
+        assert_eq!(imported.s_g2(), params.s_g2());
+        check_params(&imported, OsRng).unwrap();
+        assert_eq!(onchain_params(&imported), onchain_params(&params));
+
+        let smaller = read_ptau(&mut Cursor::new(&file), Some(2)).unwrap();
+        assert_eq!(smaller.get_g(), &params.get_g()[..4]);
+        assert_eq!(srs_fingerprint(&smaller), srs_fingerprint(&params));
+        assert!(read_ptau(&mut Cursor::new(&file), Some(power + 1)).is_err());
+
+        let mut not_ptau = file.clone();
+        not_ptau[0] = b'x';
+        assert!(read_ptau(&mut Cursor::new(&not_ptau), None).is_err());
+        // the modulus in the header is the bn254 base field modulus.
+        let header = 4 + 4 + 4 + (12 + 128 * (1 << power)) + 12;
+        let mut other_curve = file.clone();
+        other_curve[header + 4 + 31] ^= 1;
+        assert!(read_ptau(&mut Cursor::new(&other_curve), None).is_err());
+        // an untrusted header is an error, not a panic.
+        let mut zero_modulus = file.clone();
+        zero_modulus[header + 4] = 0;
+        assert!(read_ptau(&mut Cursor::new(&zero_modulus), None).is_err());
+        for bad_power in [62, 63, u32::MAX] {
+            let mut too_large = file.clone();
+            too_large[header + 36..header + 40].copy_from_slice(&bad_power.to_le_bytes());
+            assert!(read_ptau(&mut Cursor::new(&too_large), Some(power)).is_err());
+        }
+        let mut huge_section = file.clone();
+        huge_section[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(read_ptau(&mut Cursor::new(&huge_section), None).is_err());
+        // the powers read are cut off.
+        assert!(read_ptau(&mut Cursor::new(&file[..file.len() - 64 * 16]), None).is_err());
+    }
+
+    /// a circuit registered by a downstream crate.
+    struct DownstreamVectorMul;
+
+    impl ExampleCircuit for DownstreamVectorMul {
+        type Circuit = vector_mul::MyCircuit<Fr>;
+
+        fn name(&self) -> &str {
+            "downstream-vector-mul"
+        }
+
+        fn circuit(&self) -> (Self::Circuit, Vec<Vec<Fr>>) {
+            let (circuit, instances) = vector_mul::get_example_circuit();
+            (circuit, vec![instances])
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = Registry::builtin();
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            [
+                "circuit-layout",
+                "serialization",
+                "shuffle",
+                "shuffle-api",
+                "simple-example",
+                "two-chip",
+                "vector-mul"
+            ]
+        );
+        registry.register(DownstreamVectorMul).unwrap();
+        assert!(registry.register(DownstreamVectorMul).is_err());
+        let unknown = registry.find("vector-add").err().unwrap().to_string();
+        assert!(unknown.contains("downstream-vector-mul"), "{}", unknown);
+
+        let params = ParamsKZG::<Bn256>::setup(K, OsRng);
+        let example = registry.find("downstream-vector-mul").unwrap();
+        let (info, _) = example.circuit_info(&params).unwrap();
+        let (builtin, _) = registry
+            .find("vector-mul")
+            .unwrap()
+            .circuit_info(&params)
+            .unwrap();
+        assert_eq!(info.serialize().unwrap(), builtin.serialize().unwrap());
+        assert_eq!(info.srs_fingerprint, Some(srs_fingerprint(&params)));
+
+        let bundle = example
+            .prove(&params, KzgVariant::Gwc, None, None, &mut OsRng)
+            .unwrap();
+        assert_eq!(bundle.instances, vec![vec![Fr::from(6); 3]]);
+        assert!(!bundle.proof.is_empty());
+        assert!(example
+            .prove(
+                &params,
+                KzgVariant::Gwc,
+                Some(&serde_json::json!({})),
+                None,
+                &mut OsRng
+            )
+            .is_err());
+
+        run_from(&registry, ["vk-gen-examples", "list-examples"]).unwrap();
+        // bad arguments are errors, not an exit of the process.
+        assert!(run_from(&registry, ["vk-gen-examples", "no-such-command"]).is_err());
+        assert!(run_from(&registry, ["vk-gen-examples", "--kzg", "list-examples"]).is_err());
+    }
+
+    #[test]
+    fn test_gen_params_args() {
+        // `-k` and `--k` are the same option.
+        let Fixture { registry, dir, .. } = &Fixture::new("gen-params");
+        for (k, name) in [("-k", "short.srs"), ("--k", "long.srs")] {
+            let output = dir.join(name);
+            run_from(
+                registry,
+                [
+                    "vk-gen-examples",
+                    "params",
+                    "gen",
+                    k,
+                    "4",
+                    "--insecure-seed",
+                    "1",
+                    "-o",
+                    output.to_str().unwrap(),
+                ],
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            std::fs::read(dir.join("short.srs")).unwrap(),
+            std::fs::read(dir.join("long.srs")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_witness_field_elements() {
+        let parse = |json| parse_witness::<Vec<FieldElement>>(&json).map(|v| field_elements(&v));
+        assert_eq!(
+            parse(serde_json::json!([6, "6", "0x06", "0x0000000000000006"])).unwrap(),
+            vec![Fr::from(6); 4]
+        );
+        assert!(parse(serde_json::json!(["1.5"])).is_err());
+        // the modulus itself is not canonical.
+        let modulus = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+        assert!(parse(serde_json::json!([modulus])).is_err());
+        assert!(parse(serde_json::json!(["0xzz"])).is_err());
+    }
+
+    #[test]
+    fn test_witness_files() {
+        let Fixture {
+            registry,
+            params,
+            dir,
+        } = &Fixture::new("witness");
+        let vector_mul = registry.find("vector-mul").unwrap();
+
+        let json = dir.join("vector-mul.json");
+        std::fs::write(&json, r#"{"a": [1, 2, 3], "b": ["4", "5", "0x06"]}"#).unwrap();
+        let toml = dir.join("vector-mul.toml");
+        std::fs::write(&toml, "a = [7, 8, 9]\nb = [1, 1, 2]\n").unwrap();
+
+        let json_bundle = vector_mul
+            .prove(
+                params,
+                KzgVariant::Gwc,
+                Some(&read_witness(&json).unwrap()),
+                None,
+                &mut OsRng,
+            )
+            .unwrap();
+        assert_eq!(
+            json_bundle.instances,
+            vec![vec![Fr::from(4), Fr::from(10), Fr::from(18)]]
+        );
+        let toml_bundle = vector_mul
+            .prove(
+                params,
+                KzgVariant::Gwc,
+                Some(&read_witness(&toml).unwrap()),
+                None,
+                &mut OsRng,
+            )
+            .unwrap();
+        assert_eq!(
+            toml_bundle.instances,
+            vec![vec![Fr::from(7), Fr::from(8), Fr::from(18)]]
+        );
+        assert_ne!(json_bundle.proof, toml_bundle.proof);
+
+        // instances which do not match the witness do not give a valid proof.
+        let wrong = serde_json::json!({"a": [1, 2, 3], "b": [4, 5, 6], "c": [4, 10, 19]});
+        assert!(vector_mul
+            .prove(params, KzgVariant::Gwc, Some(&wrong), None, &mut OsRng)
+            .is_err());
+        // the number of elements is part of the shape.
+        let short = serde_json::json!({"a": [1, 2], "b": [4, 5]});
+        assert!(vector_mul
+            .prove(params, KzgVariant::Gwc, Some(&short), None, &mut OsRng)
+            .is_err());
+
+        // one valid witness per example, in its schema, and the instances it is proved with.
+        // the circuit-layout and shuffle-api ones change fixed columns.
+        let original: Vec<Vec<u64>> = (0..4)
+            .map(|i| (0..32).map(|j| i * 32 + j).collect())
+            .collect();
+        let shuffled: Vec<Vec<u64>> = original
+            .iter()
+            .map(|column| column.iter().rev().copied().collect())
+            .collect();
+        let witnesses = [
+            (
+                "circuit-layout",
+                serde_json::json!({"a": 3, "lookup_table": [0, 3]}),
+                vec![],
+            ),
+            (
+                "serialization",
+                serde_json::json!({"value": 7}),
+                vec![vec![Fr::from(7)]],
+            ),
+            (
+                "shuffle",
+                serde_json::json!({"original": original, "shuffled": shuffled}),
+                vec![],
+            ),
+            (
+                "shuffle-api",
+                serde_json::json!({
+                    "input_0": [3, 5, 7, 3],
+                    "input_1": [30, 50, 70, 30],
+                    "shuffle_0": [7, 3, 5, 3],
+                    "shuffle_1": [70, 30, 50, 30]
+                }),
+                vec![],
+            ),
+            (
+                "simple-example",
+                serde_json::json!({"a": 2, "b": 3}),
+                vec![vec![Fr::from(7 * 4 * 9)]],
+            ),
+            (
+                "two-chip",
+                serde_json::json!({"a": 1, "b": 2, "c": 3}),
+                vec![vec![Fr::from(9)]],
+            ),
+            (
+                "vector-mul",
+                serde_json::json!({"a": [1, 2, 3], "b": [4, 5, 6]}),
+                vec![vec![Fr::from(4), Fr::from(10), Fr::from(18)]],
+            ),
+        ];
+        assert_eq!(
+            witnesses.iter().map(|(name, ..)| *name).collect::<Vec<_>>(),
+            registry.names().collect::<Vec<_>>()
+        );
+        for (name, witness, instances) in &witnesses {
+            let example = registry.find(name).unwrap();
+            let bundle = example
+                .prove(params, KzgVariant::Shplonk, Some(witness), None, &mut OsRng)
+                .unwrap_or_else(|e| panic!("{}: {:?}", name, e));
+            assert_eq!(&bundle.instances, instances, "{}", name);
+            example
+                .verify(params, &bundle, Some(witness), None)
+                .unwrap_or_else(|e| panic!("{}: {:?}", name, e));
+            // an empty witness misses its fields.
+            assert!(example
+                .prove(
+                    params,
+                    KzgVariant::Gwc,
+                    Some(&serde_json::json!({})),
+                    None,
+                    &mut OsRng
+                )
+                .is_err());
+        }
+        // the rows of the shuffled matrix must be a permutation of the original ones.
+        let mut not_shuffled = shuffled.clone();
+        not_shuffled[0][0] += 1;
+        let shuffle = registry.find("shuffle").unwrap();
+        assert!(shuffle
+            .prove(
+                params,
+                KzgVariant::Gwc,
+                Some(&serde_json::json!({"original": original, "shuffled": not_shuffled})),
+                None,
+                &mut OsRng
+            )
+            .is_err());
+        // and it has 4 columns of 32 rows.
+        assert!(shuffle
+            .prove(
+                params,
+                KzgVariant::Gwc,
+                Some(&serde_json::json!({"original": original[..3], "shuffled": shuffled[..3]})),
+                None,
+                &mut OsRng
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_proof_bundle() {
+        let Fixture {
+            registry,
+            params,
+            dir,
+        } = &Fixture::new("bundle");
+        let vector_mul = registry.find("vector-mul").unwrap();
+        let bundle = vector_mul
+            .prove(params, KzgVariant::Shplonk, None, None, &mut OsRng)
+            .unwrap();
+        let (info, _) = vector_mul.circuit_info(params).unwrap();
+        assert_eq!(bundle.shape_fingerprint, info.fingerprint().unwrap());
+        assert_eq!(bundle.transcript, TranscriptKind::Keccak256);
+
+        let path = dir.join("vector-mul-shplonk.bundle.json");
+        bundle.write(&path).unwrap();
+        let read = ProofBundle::read(&path).unwrap();
+        assert_eq!(read, bundle);
+        // instances are big endian hex, as in witness files.
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let six = &json["instances"][0][0];
+        assert_eq!(six, &serde_json::json!(format!("0x{:064x}", 6)));
+        assert_eq!(
+            parse_witness::<FieldElement>(six).unwrap().0,
+            bundle.instances[0][0]
+        );
+
+        // the payload of the bundle is the one built right after proving.
+        let api = VerifierApi::new("0x1");
+        assert_eq!(
+            read.verify_payload(&api, "0x2".to_string(), "0x3".to_string()),
+            api.verify_proof(
+                "0x2".to_string(),
+                "0x3".to_string(),
+                &bundle.instances,
+                bundle.proof.clone(),
+                KzgVariant::Shplonk,
+            )
+        );
+        let path = path.to_str().unwrap();
+        let output = dir.to_str().unwrap();
+        run_from(
+            registry,
+            [
+                "vk-gen-examples",
+                "build-verify-payload",
+                "--bundle",
+                path,
+                "--param-address",
+                "0x2",
+                "--circuit-address",
+                "0x3",
+                "-o",
+                output,
+            ],
+        )
+        .unwrap();
+        assert!(dir.join("vector-mul-shplonk-verify-proof.json").exists());
+        // `--verify-func` renames the verify function, for modules other than `verifier_api`.
+        run_from(
+            registry,
+            [
+                "vk-gen-examples",
+                "--verify-func",
+                "verify",
+                "build-verify-payload",
+                "--bundle",
+                path,
+                "--param-address",
+                "0x2",
+                "--circuit-address",
+                "0x3",
+                "-o",
+                output,
+            ],
+        )
+        .unwrap();
+        let payload: EntryFunctionArgumentsJSON = serde_json::from_str(
+            &std::fs::read_to_string(dir.join("vector-mul-shplonk-verify-proof.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(payload.function_id, "0x1::verifier_api::verify");
+        // the circuit address can be derived from the account that published it.
+        run_from(
+            registry,
+            [
+                "vk-gen-examples",
+                "build-verify-payload",
+                "--bundle",
+                path,
+                "--param-address",
+                "0x2",
+                "--circuit-deployer",
+                "0xcafe",
+                "--object-seed",
+                "vector_mul",
+                "-o",
+                output,
+            ],
+        )
+        .unwrap();
+        let payload: EntryFunctionArgumentsJSON = serde_json::from_str(
+            &std::fs::read_to_string(dir.join("vector-mul-shplonk-verify-proof.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            payload.args[1],
+            MoveArg::Address(
+                "0xc183ceffac1717fd3df0013d8c67795e70012f16ff25551798842f3a58106605".to_string()
+            )
+        );
+        // a seed without a deployer is an error, not ignored.
+        assert!(run_from(
+            registry,
+            [
+                "vk-gen-examples",
+                "build-verify-payload",
+                "--bundle",
+                path,
+                "--param-address",
+                "0x2",
+                "--circuit-address",
+                "0x3",
+                "--object-seed",
+                "vector_mul",
+                "-o",
+                output,
+            ],
+        )
+        .is_err());
+
+        vector_mul.verify(params, &read, None, None).unwrap();
+        // a bundle of another circuit, or with other instances, does not verify.
+        let mut other_shape = read.clone();
+        other_shape.shape_fingerprint[0] ^= 1;
+        assert!(vector_mul.verify(params, &other_shape, None, None).is_err());
+        assert!(registry
+            .find("two-chip")
+            .unwrap()
+            .verify(params, &read, None, None)
+            .is_err());
+        let mut other_instances = read.clone();
+        other_instances.instances[0][0] += Fr::ONE;
+        assert!(vector_mul
+            .verify(params, &other_instances, None, None)
+            .is_err());
+        // nor does it with params of another setup.
+        let other_params = ParamsKZG::<Bn256>::setup(K, OsRng);
+        assert!(vector_mul.verify(&other_params, &read, None, None).is_err());
+    }
+
+    #[test]
+    fn test_batch_proof() {
+        // the payload `build-verify-proof-aptos-txn --batch 2` writes.
+        let Fixture {
+            registry, params, ..
+        } = &Fixture::new("batch");
+        let vector_mul = registry.find("vector-mul").unwrap();
+        let (report, instances) = vector_mul
+            .prove_batch(params, KzgVariant::Gwc, None, 2, None, &mut OsRng)
+            .unwrap();
+        assert!(report.verify_time.is_some());
+        assert_eq!(instances.len(), 2);
+        let single = vector_mul
+            .prove(params, KzgVariant::Gwc, None, None, &mut OsRng)
+            .unwrap();
+        assert!(instances.iter().all(|i| i == &single.instances));
+
+        let (circuit, _) = vector_mul::get_example_circuit::<Fr>();
+        let vk = keygen_vk(params, &circuit).unwrap();
+        verify_batch(params, &vk, &instances, &report.proof, KzgVariant::Gwc).unwrap();
+        let api = VerifierApi::new("0x1");
+        let payload =
+            report.verify_batch_payload(&api, "0x2".to_string(), "0x3".to_string(), &instances);
+        assert_eq!(payload.function_id, "0x1::verifier_api::verify_batch_proof");
+
+        assert!(vector_mul
+            .prove_batch(params, KzgVariant::Gwc, None, 0, None, &mut OsRng)
+            .is_err());
+    }
+
+    #[test]
+    fn test_key_cache() {
+        let Fixture {
+            registry,
+            params,
+            dir,
+        } = &Fixture::new("keys");
+        let keys = KeyCache::new(dir);
+        let shape = |vk: &VerifyingKey<G1Affine>| {
+            generate_circuit_info_from_vk(params, vk)
+                .unwrap()
+                .with_srs_fingerprint(params)
+                .fingerprint()
+                .unwrap()
+        };
+
+        // the second proof is made with the keys of the first.
+        let vector_mul = registry.find("vector-mul").unwrap();
+        let path = keys.key_path("vector-mul", params);
+        let first = vector_mul
+            .prove(params, KzgVariant::Gwc, None, Some(&keys), &mut OsRng)
+            .unwrap();
+        for extension in ["pk", "vk", "shape"] {
+            assert!(path.with_extension(extension).exists(), "{}", extension);
+        }
+        let cached = vector_mul
+            .prove(params, KzgVariant::Gwc, None, Some(&keys), &mut OsRng)
+            .unwrap();
+        assert_eq!(cached.shape_fingerprint, first.shape_fingerprint);
+        vector_mul
+            .verify(params, &first, None, Some(&keys))
+            .unwrap();
+
+        // keys of another circuit under the same name are generated again.
+        let (two_chip, _) = two_chip::TwoChip.circuit();
+        let vk = keys.verifying_key("vector-mul", params, &two_chip).unwrap();
+        assert_eq!(shape(&vk), shape(&keygen_vk(params, &two_chip).unwrap()));
+        assert_ne!(shape(&vk), first.shape_fingerprint);
+        assert!(!path.with_extension("pk").exists());
+
+        // keys of other fixed values are generated again, the stale ones are never reused.
+        let (circuit, _) = shuffle_api::ShuffleApi.circuit();
+        let (other_fixed, _) = shuffle_api::ShuffleApi
+            .witness_from_json(&serde_json::json!({
+                "input_0": [1, 2, 4, 1],
+                "input_1": [10, 20, 40, 11],
+                "shuffle_0": [4, 1, 1, 2],
+                "shuffle_1": [40, 10, 11, 20],
+            }))
+            .unwrap();
+        let preprocessing = |circuit| preprocessing_fingerprint(K, circuit).unwrap();
+        assert_eq!(
+            preprocessing(&circuit),
+            preprocessing(&shuffle_api::ShuffleApi.circuit().0)
+        );
+        assert_ne!(preprocessing(&circuit), preprocessing(&other_fixed));
+        let pk = keys.proving_key("shuffle-api", params, &circuit).unwrap();
+        let fresh = shape(&keygen_vk(params, &other_fixed).unwrap());
+        assert_ne!(shape(pk.get_vk()), fresh);
+        let other = keys
+            .proving_key("shuffle-api", params, &other_fixed)
+            .unwrap();
+        assert_eq!(shape(other.get_vk()), fresh);
+        let vk = keys.verifying_key("shuffle-api", params, &circuit).unwrap();
+        assert_eq!(shape(&vk), shape(pk.get_vk()));
+
+        // a lookup table is a fixed column as well.
+        let circuit_layout = registry.find("circuit-layout").unwrap();
+        let other_table = serde_json::json!({"a": 3, "lookup_table": [0, 3]});
+        let bundle = circuit_layout
+            .prove(params, KzgVariant::Gwc, None, Some(&keys), &mut OsRng)
+            .unwrap();
+        let other_bundle = circuit_layout
+            .prove(
+                params,
+                KzgVariant::Gwc,
+                Some(&other_table),
+                Some(&keys),
+                &mut OsRng,
+            )
+            .unwrap();
+        assert_ne!(other_bundle.shape_fingerprint, bundle.shape_fingerprint);
+        circuit_layout
+            .verify(params, &other_bundle, Some(&other_table), None)
+            .unwrap();
+
+        // regenerating overwrites keys which would have been reused.
+        let regenerated = KeyCache::new(dir)
+            .with_regenerate(true)
+            .proving_key("shuffle-api", params, &circuit)
+            .unwrap();
+        assert_eq!(shape(regenerated.get_vk()), shape(pk.get_vk()));
+    }
+
+    /// sha3-256 of the proof of every example, from seeded params, the default witness and a seeded prover.
+    /// A change of a default witness, of the keys or of the prover shows up here.
+    /// After an intended change, write the hashes again with `UPDATE_GOLDEN=1 cargo test golden`.
+    #[test]
+    fn test_golden_proofs() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/golden-proofs.txt");
+        let registry = Registry::builtin();
+        let params = ParamsKZG::<Bn256>::setup(K, StdRng::seed_from_u64(0));
+        let mut hashes = String::new();
+        for name in registry.names() {
+            let example = registry.find(name).unwrap();
+            for kzg in KzgVariant::ALL {
+                let prove_seeded = |seed| {
+                    example
+                        .prove(&params, kzg, None, None, &mut StdRng::seed_from_u64(seed))
+                        .unwrap()
+                        .proof
+                };
+                let proof = prove_seeded(7);
+                assert_ne!(prove_seeded(8), proof, "{} {}", name, kzg);
+                hashes += &format!(
+                    "{} {} {}\n",
+                    name,
+                    kzg,
+                    hex::encode(Sha3_256::digest(&proof))
+                );
+            }
+        }
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &hashes).unwrap();
+        }
+        let golden = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!(
+                "{} is missing, write it with UPDATE_GOLDEN=1",
+                path.display()
+            )
+        });
+        assert_eq!(hashes, golden);
+    }
+
+    #[test]
+    fn test_seeded_bundles() {
+        // `--seed` writes the same bundle on every run.
+        let Fixture {
+            registry,
+            params,
+            dir,
+        } = &Fixture::new("seed");
+        let param_path = dir.join("params.srs");
+        params
+            .write(&mut std::fs::File::create(&param_path).unwrap())
+            .unwrap();
+        let prove_cli = |output: &str, seed: &str| {
+            let output = dir.join(output);
+            run_from(
+                registry,
+                [
+                    "vk-gen-examples",
+                    "--param-path",
+                    param_path.to_str().unwrap(),
+                    "prove",
+                    "--example",
+                    "vector-mul",
+                    "--kzg",
+                    "gwc",
+                    "--seed",
+                    seed,
+                    "-o",
+                    output.to_str().unwrap(),
+                ],
+            )
+            .unwrap();
+            std::fs::read(output.join("vector-mul-gwc.bundle.json")).unwrap()
+        };
+        let bundle = prove_cli("first", "42");
+        assert_eq!(prove_cli("second", "42"), bundle);
+        assert_ne!(prove_cli("third", "43"), bundle);
+    }
+
+    #[test]
+    fn test_publish_with_srs() {
+        // the binding to the params is opt-in, for modules without `publish_circuit_with_srs`.
+        let Fixture {
+            registry,
+            params,
+            dir,
+        } = &Fixture::new("publish-srs");
+        let param_path = dir.join("params.srs");
+        params
+            .write(&mut std::fs::File::create(&param_path).unwrap())
+            .unwrap();
+        let api = VerifierApi::new("0x1");
+        let publish_cli = |output: &str, bind_srs: bool| {
+            let output = dir.join(output);
+            let args = [
+                "vk-gen-examples",
+                "--param-path",
+                param_path.to_str().unwrap(),
+                "build-publish-vk-aptos-txn",
+                "--example",
+                "vector-mul",
+                "-o",
+                output.to_str().unwrap(),
+            ];
+            let bind = bind_srs.then_some("--bind-srs");
+            run_from(registry, args.into_iter().chain(bind)).unwrap();
+            let json =
+                std::fs::read_to_string(output.join("VectorMul-publish-circuit.json")).unwrap();
+            api.read_payload(&json).unwrap()
+        };
+        let plain = publish_cli("plain", false);
+        assert_eq!(plain.function_id, "0x1::verifier_api::publish_circuit");
+        assert_eq!(plain.args.len(), 11);
+        let bound = publish_cli("bound", true);
+        assert_eq!(
+            bound.function_id,
+            "0x1::verifier_api::publish_circuit_with_srs"
+        );
+        assert_eq!(bound.args[..11], plain.args[..]);
+        assert_eq!(
+            bound.args[11],
+            MoveArg::Hex(srs_fingerprint(params).to_vec())
+        );
+    }
+}
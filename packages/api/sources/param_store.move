@@ -1,7 +1,11 @@
 module verifier_api::param_store {
     use std::option;
     use std::error;
-    
+    use std::hash;
+    use std::vector;
+    use aptos_framework::account;
+    use aptos_framework::object;
+
     use halo2_common::bn254_utils;
     use halo2_common::params::{Self, Params};
 
@@ -30,10 +34,34 @@ module verifier_api::param_store {
         });
     }
 
+    /// create the params under a named object of sender,
+    /// its address is `object::create_object_address(&sender, seed)`.
+    public entry fun create_to_object(sender: &signer, seed: vector<u8>, g1: vector<u8>, g2: vector<u8>, s_g2: vector<u8>) {
+        let constructor_ref = object::create_named_object(sender, seed);
+        create(&object::generate_signer(&constructor_ref), g1, g2, s_g2);
+    }
+
+    /// create the params under a resource account of sender,
+    /// its address is `account::create_resource_address(&sender, seed)`.
+    public entry fun create_to_resource_account(sender: &signer, seed: vector<u8>, g1: vector<u8>, g2: vector<u8>, s_g2: vector<u8>) {
+        let (resource_signer, _) = account::create_resource_account(sender, seed);
+        create(&resource_signer, g1, g2, s_g2);
+    }
+
     public fun get_params(addr: address): Params acquires StoredParams {
         to_params(borrow_global<StoredParams>(addr))
     }
 
+    #[view]
+    /// sha3-256 of g1, g2 and s_g2, the `srs_fingerprint` of shape-generator.
+    public fun fingerprint(addr: address): vector<u8> acquires StoredParams {
+        let params = borrow_global<StoredParams>(addr);
+        let bytes = params.g1;
+        vector::append(&mut bytes, params.g2);
+        vector::append(&mut bytes, params.s_g2);
+        hash::sha3_256(bytes)
+    }
+
     /// destory a params
     public fun destroy(params: StoredParams) {
         let StoredParams {g1: _, g2:_,s_g2:_} = params;
@@ -1,20 +1,34 @@
 module verifier_api::verifier_api {
     use std::error;
+    use std::vector;
     use aptos_std::bn254_algebra::Fr;
+    use aptos_framework::account;
+    use aptos_framework::object;
 
-    use verifier_api::param_store::get_params;
+    use verifier_api::param_store::{Self, get_params};
 
-    use halo2_verifier::halo2_verifier::{verify_single, verify_single_vm};
+    use halo2_verifier::halo2_verifier::{verify_batch, verify_single, verify_single_vm};
     use halo2_verifier::protocol::{Self, Protocol};
     use halo2_verifier::public_inputs::PublicInputs;
 
     const VERIFY_PROOF_FAILURE: u64 = 1;
+    const PARAMS_MISMATCH: u64 = 2;
+    const INVALID_SRS_FINGERPRINT: u64 = 3;
+
+    /// the length of a sha3-256 srs fingerprint.
+    const SRS_FINGERPRINT_LEN: u64 = 32;
 
     /// wrapper on protocol
     struct Circuit has key {
         protocol: Protocol,
     }
 
+    /// sha3-256 of the params a circuit was keyed with, stored next to its `Circuit`
+    /// by `publish_circuit_with_srs`. circuits published with `publish_circuit` have none.
+    struct CircuitParams has key {
+        srs_fingerprint: vector<u8>,
+    }
+
     /// Publish the circuit under sender account
     public entry fun publish_circuit(
         sender: &signer,
@@ -38,16 +52,149 @@ module verifier_api::verifier_api {
         move_to(sender, Circuit { protocol: proto });
     }
 
+    /// Publish the circuit under sender account, with the fingerprint of the params it was keyed with.
+    /// Proofs are then only verified against params of that fingerprint.
+    public entry fun publish_circuit_with_srs(
+        sender: &signer,
+        general_info: vector<vector<u8>>,
+        advice_queries: vector<vector<u8>>,
+        instance_queries: vector<vector<u8>>,
+        fixed_queries: vector<vector<u8>>,
+        permutation_columns: vector<vector<u8>>,
+        fields_pool: vector<vector<u8>>,
+        gates: vector<vector<u8>>,
+        lookups_input_exprs: vector<vector<u8>>,
+        lookups_table_exprs: vector<vector<u8>>,
+        shuffle_input_exprs: vector<vector<u8>>,
+        shuffle_exprs: vector<vector<u8>>,
+        srs_fingerprint: vector<u8>,
+    ) {
+        assert!(
+            vector::length(&srs_fingerprint) == SRS_FINGERPRINT_LEN,
+            error::invalid_argument(INVALID_SRS_FINGERPRINT)
+        );
+        publish_circuit(
+            sender, general_info, advice_queries, instance_queries, fixed_queries, permutation_columns,
+            fields_pool, gates, lookups_input_exprs, lookups_table_exprs, shuffle_input_exprs,
+            shuffle_exprs
+        );
+        move_to(sender, CircuitParams { srs_fingerprint });
+    }
+
+    /// Publish the circuit under a named object of sender,
+    /// its address is `object::create_object_address(&sender, seed)`.
+    public entry fun publish_circuit_to_object(
+        sender: &signer,
+        seed: vector<u8>,
+        general_info: vector<vector<u8>>,
+        advice_queries: vector<vector<u8>>,
+        instance_queries: vector<vector<u8>>,
+        fixed_queries: vector<vector<u8>>,
+        permutation_columns: vector<vector<u8>>,
+        fields_pool: vector<vector<u8>>,
+        gates: vector<vector<u8>>,
+        lookups_input_exprs: vector<vector<u8>>,
+        lookups_table_exprs: vector<vector<u8>>,
+        shuffle_input_exprs: vector<vector<u8>>,
+        shuffle_exprs: vector<vector<u8>>,
+    ) {
+        let constructor_ref = object::create_named_object(sender, seed);
+        let object_signer = object::generate_signer(&constructor_ref);
+        publish_circuit(
+            &object_signer, general_info, advice_queries, instance_queries, fixed_queries, permutation_columns,
+            fields_pool, gates, lookups_input_exprs, lookups_table_exprs, shuffle_input_exprs,
+            shuffle_exprs
+        );
+    }
+
+    /// Publish the circuit under a resource account of sender,
+    /// its address is `account::create_resource_address(&sender, seed)`.
+    public entry fun publish_circuit_to_resource_account(
+        sender: &signer,
+        seed: vector<u8>,
+        general_info: vector<vector<u8>>,
+        advice_queries: vector<vector<u8>>,
+        instance_queries: vector<vector<u8>>,
+        fixed_queries: vector<vector<u8>>,
+        permutation_columns: vector<vector<u8>>,
+        fields_pool: vector<vector<u8>>,
+        gates: vector<vector<u8>>,
+        lookups_input_exprs: vector<vector<u8>>,
+        lookups_table_exprs: vector<vector<u8>>,
+        shuffle_input_exprs: vector<vector<u8>>,
+        shuffle_exprs: vector<vector<u8>>,
+    ) {
+        let (resource_signer, _) = account::create_resource_account(sender, seed);
+        publish_circuit(
+            &resource_signer, general_info, advice_queries, instance_queries, fixed_queries, permutation_columns,
+            fields_pool, gates, lookups_input_exprs, lookups_table_exprs, shuffle_input_exprs,
+            shuffle_exprs
+        );
+    }
+
+    /// `publish_circuit_with_srs` under a named object of sender,
+    /// its address is `object::create_object_address(&sender, seed)`.
+    public entry fun publish_circuit_with_srs_to_object(
+        sender: &signer,
+        seed: vector<u8>,
+        general_info: vector<vector<u8>>,
+        advice_queries: vector<vector<u8>>,
+        instance_queries: vector<vector<u8>>,
+        fixed_queries: vector<vector<u8>>,
+        permutation_columns: vector<vector<u8>>,
+        fields_pool: vector<vector<u8>>,
+        gates: vector<vector<u8>>,
+        lookups_input_exprs: vector<vector<u8>>,
+        lookups_table_exprs: vector<vector<u8>>,
+        shuffle_input_exprs: vector<vector<u8>>,
+        shuffle_exprs: vector<vector<u8>>,
+        srs_fingerprint: vector<u8>,
+    ) {
+        let constructor_ref = object::create_named_object(sender, seed);
+        let object_signer = object::generate_signer(&constructor_ref);
+        publish_circuit_with_srs(
+            &object_signer, general_info, advice_queries, instance_queries, fixed_queries, permutation_columns,
+            fields_pool, gates, lookups_input_exprs, lookups_table_exprs, shuffle_input_exprs,
+            shuffle_exprs, srs_fingerprint
+        );
+    }
+
+    /// `publish_circuit_with_srs` under a resource account of sender,
+    /// its address is `account::create_resource_address(&sender, seed)`.
+    public entry fun publish_circuit_with_srs_to_resource_account(
+        sender: &signer,
+        seed: vector<u8>,
+        general_info: vector<vector<u8>>,
+        advice_queries: vector<vector<u8>>,
+        instance_queries: vector<vector<u8>>,
+        fixed_queries: vector<vector<u8>>,
+        permutation_columns: vector<vector<u8>>,
+        fields_pool: vector<vector<u8>>,
+        gates: vector<vector<u8>>,
+        lookups_input_exprs: vector<vector<u8>>,
+        lookups_table_exprs: vector<vector<u8>>,
+        shuffle_input_exprs: vector<vector<u8>>,
+        shuffle_exprs: vector<vector<u8>>,
+        srs_fingerprint: vector<u8>,
+    ) {
+        let (resource_signer, _) = account::create_resource_account(sender, seed);
+        publish_circuit_with_srs(
+            &resource_signer, general_info, advice_queries, instance_queries, fixed_queries, permutation_columns,
+            fields_pool, gates, lookups_input_exprs, lookups_table_exprs, shuffle_input_exprs,
+            shuffle_exprs, srs_fingerprint
+        );
+    }
+
     public entry fun verify_proof_gwc(param_address: address,
                                   circuit_address: address,
                                   instances: vector<vector<vector<u8>>>,
-                                  proof: vector<u8>) acquires Circuit {
+                                  proof: vector<u8>) acquires Circuit, CircuitParams {
         verify_proof(param_address,circuit_address,instances,proof, 0);
     }
     public entry fun verify_proof_shplonk(param_address: address,
                                       circuit_address: address,
                                       instances: vector<vector<vector<u8>>>,
-                                      proof: vector<u8>) acquires Circuit {
+                                      proof: vector<u8>) acquires Circuit, CircuitParams {
         verify_proof(param_address,circuit_address,instances,proof, 1);
     }
 
@@ -58,13 +205,41 @@ module verifier_api::verifier_api {
         instances: vector<vector<vector<u8>>>,
         proof: vector<u8>,
         kzg_variant: u8,
-    ) acquires Circuit {
+    ) acquires Circuit, CircuitParams {
         let params = get_params(param_address);
         let circuit = borrow_global<Circuit>(circuit_address);
         let protocol = &circuit.protocol;
+        assert_params_match(param_address, circuit_address);
         assert!(verify_single(&params, protocol, instances, proof, kzg_variant), error::aborted(VERIFY_PROOF_FAILURE));
     }
 
+    /// verify one proof of several instances of the circuit, 0: gwc, 1: shplonk
+    /// instances are indexed by circuit instance, column, then row.
+    public entry fun verify_batch_proof(
+        param_address: address,
+        circuit_address: address,
+        instances: vector<vector<vector<vector<u8>>>>,
+        proof: vector<u8>,
+        kzg_variant: u8,
+    ) acquires Circuit, CircuitParams {
+        let params = get_params(param_address);
+        let circuit = borrow_global<Circuit>(circuit_address);
+        let protocol = &circuit.protocol;
+        assert_params_match(param_address, circuit_address);
+        assert!(verify_batch(&params, protocol, instances, proof, kzg_variant), error::aborted(VERIFY_PROOF_FAILURE));
+    }
+
+    /// mock verify batch proof, do nothing
+    public entry fun mock_verify_batch_proof(
+        _param_address: address,
+        _circuit_address: address,
+        _instances: vector<vector<vector<vector<u8>>>>,
+        _proof: vector<u8>,
+        _kzg_variant: u8,
+    ) {
+        // do nothing
+    }
+
     /// mock verify proof, do nothing
     public entry fun mock_verify_proof(
         _param_address: address,
@@ -85,10 +260,11 @@ module verifier_api::verifier_api {
         public_inputs: PublicInputs<Fr>,
         proof: vector<u8>,
         kzg_variant: u8,
-    ): bool acquires Circuit {
+    ): bool acquires Circuit, CircuitParams {
         let params = get_params(param_address);
         let circuit = borrow_global<Circuit>(circuit_address);
         let protocol = &circuit.protocol;
+        assert_params_match(param_address, circuit_address);
         verify_single_vm(&params, protocol, public_inputs, proof, kzg_variant)
     }
 
@@ -103,6 +279,17 @@ module verifier_api::verifier_api {
         true
     }
 
+    /// abort if the circuit was keyed with other params than the ones at `param_address`.
+    /// circuits published without a fingerprint are not checked.
+    fun assert_params_match(param_address: address, circuit_address: address) acquires CircuitParams {
+        if (exists<CircuitParams>(circuit_address)) {
+            assert!(
+                borrow_global<CircuitParams>(circuit_address).srs_fingerprint == param_store::fingerprint(param_address),
+                error::invalid_argument(PARAMS_MISMATCH)
+            );
+        }
+    }
+
     /// destory a circuit
     public fun destroy(circuit: Circuit) {
         let Circuit { protocol: _ } = circuit;
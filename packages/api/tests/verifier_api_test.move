@@ -2,6 +2,8 @@
 module verifier_api::verifier_api_test {
     use std::vector;
     use aptos_std::crypto_algebra;
+    use aptos_framework::account;
+    use aptos_framework::object;
     use verifier_api::param_store;
     use std::signer;
     use std::unit_test;
@@ -22,54 +24,8 @@ module verifier_api::verifier_api_test {
         let signers = unit_test::create_signers_for_testing(1);
         let signer = vector::pop_back(&mut signers);
         param_store::create(&signer, TESTING_G1, TESTING_G2, TESTING_S_G2);
-        // protocol of example vector-mul
-        // generated by `cargo run --release --  --param-path params/challenge_0078-kzg_bn254_16.srs --verifier-address 0xcfae5b6bd579e7aff4274aeca434bb500c024b89c139b545c6eeb27bfafea8c1 build-publish-vk-aptos-txn --example vector-mul -o vk_deployment`
-        verifier_api::publish_circuit(&signer,vector[
-            x"3f8c4673ba5c3eb53ddb38c02cfa47483c4d6a25fbc64ea44c50309fbd413d2c",
-            x"a7c40e6e753cfd404ff8e10e1352a3eb77c8e0495bf1d9b7c67410ce4f2a5a98",
-            x"f1ad77997188215fb04be428716b389ff29c6ef944e36ab74ffaa9d192d324858d6c5ae20545006f1f4c93446419c848a95dd8e567a2990c1cba488d4a196f0fa9f81ad6c30148acd3011bfd4d6f9a129c577dd059acb81a26d86e7afab696aba66350b2d5606122fa883ea1232fc384ffae8f8e3db990ef760506abf2f5f284",
-            x"0c",
-            x"01000000",
-            x"03000000",
-            x"0100000000000000",
-            x"0100000000000000",
-            x"000000",
-            x"",
-            x"00",
-            x"00"
-        ],
-            vector[
-                x"01000000000100000000",
-                x"01010000000100000000",
-                x"01020000000100000000"
-            ],
-            vector[
-                x"03000000000100000000"
-            ],
-            vector[x"02000000000100000000"],
-            vector[
-                x"0300000000",
-                x"0100000000",
-                x"0101000000",
-                x"0102000000"
-            ],
-            vector[],
-            vector[
-                x"080200070803000301060302"
-            ],
-            vector[],
-            vector[],
-            vector[],
-            vector[]
-        );
-        let proof = x"3681c1d1817d8b98dd7b522ccecf2cf190a4a8e1cf6f8a743b7acdcab33e26132211205f30ce2b988edbc8bcea6b4c5970280f1aa1b8248264ca8e265575878d00bd3fc17b08e9dd6bd7f6484ab6a79997c68d542eacd86167eb42d169f42690917e64f9a25d79b2a35c01e50fd522cf1e0f1972a268ab99fc2a66fab4c1fbaac7f928a6bbe3678f2210aca958e4274b36cbabecc23249c146db0e5c6d5f0e8e27b134238e08c4edf54b874dd1d5a0176872fd428d2e72765a86ed36f1c82600435ffc17a391a89cdc95642ce72e58ef8f6a8d13844e730c75d291e30bf4dca2137aded2207d1fc5226f90535c4758d1dc436edda9f049067bbb465c747c6001d61e52ea8e5c441ed55f4244c85b515f7fd28229641b829594e612783292440da0457df8f2ca01b837c681e9a8f34bb722082aa62b0f7a6eaaf9b874197f309707266faa9f823e2d4ded37f2a001ebbc2bc1e58a912673e088d643ee5af23a1a7aec68c383498a080b617c98af567a96510c1f5ac6988fb2cdc7a6af0ee1fd26f4e8557b441079497d91be7c59810c3148c9a479f893261e7c0c5ee008577f2718fd4aaa5ba24c4421962a05be3e0ec807f7f46aa55ca1b5bb78cf5a6bc27b0139d5e24bca1b57b1d66bd3c3ad17d6c219c85d14f8ce7311ad1f12453db020300d119d602a3bb5ed00f1b0718bbff5ff03ebe3d65f8d94200769bd1f90a4952dbd5f54d0adb5c309fe6e01fc24f9baef6c6b571c8049f234c0c139bc01a3df293805bb83f48a4c26fca011e67bf7080f01ad3151725c4a391f97a0d7b34d3517e16a4cec8a676c67a133c3d42ec2d644b05feaa6ac0ca09c44134fe77740bc19abd4a981d7fd95cebb4bee3787d9d163e57d161ded20203f5ab3f051ecfec71a4cb0716ada6eca73949c893f83a75d6a69b918604876359076cc58817baa8828a3e127b5111be026655ae8edefd79ced3e5bd05521bdbe3198404327c0408f161b6a98303867cb77b3e6ce674a457b0ecd738107db2966534797adb039b48d0d8fe6df89f67d84f42cb19b2d3799b168ef550450f878b59e4fe1bb9c8d64871e896eedc50241740c64cbab5aa2415043ef5c095529370fb03adbc6ec8e22852c7aa92729889a956df02a7fdc66db20d151805e7afb33e9d4c53ea41fa90bf822ea572c361cf6136c7d6ba50275bff40721f888216b4d19f3dd18d7768a41b2053039e17cdbd0d70721e76224f2bb5b541c03123da922b23948b98a05a92bec0eb917bf500dd0947ca27863faf9eed01a7d81852a3016bc0e1a36457ccb6632278448d6e99bd631cbd007de80ce1b693601b723a8e3b2c75f781bc2124ee7cc076e6b66d210a85d8adae856bf722facb2d811332ab1f343f8a28d94a926ca229d652dc60060c241e7548394cd4885a67f725551a85048295e012b333ab297fd007f77e22ecb19e7ef1ee6a7cbdc3e9945beb9638ef065a697c46d79c0de01a793";
-
-        let instances = vector[
-            x"0600000000000000000000000000000000000000000000000000000000000000",
-            x"0600000000000000000000000000000000000000000000000000000000000000",
-            x"0600000000000000000000000000000000000000000000000000000000000000"
-        ];
-        verifier_api::verify_proof_gwc(signer::address_of(&signer), signer::address_of(&signer),  vector::singleton(instances), proof);
+        publish_vector_mul(&signer);
+        verifier_api::verify_proof_gwc(signer::address_of(&signer), signer::address_of(&signer), vector::singleton(vector_mul_instances()), vector_mul_proof());
     }
 
     #[test(s = @aptos_std)]
@@ -129,4 +85,173 @@ module verifier_api::verifier_api_test {
         );
     }
 
+    #[test(s = @aptos_std)]
+    public fun check_params_fingerprint(s: &signer) {
+        crypto_algebra::enable_cryptography_algebra_natives(s);
+        let signers = unit_test::create_signers_for_testing(1);
+        let signer = vector::pop_back(&mut signers);
+        param_store::create(&signer, TESTING_G1, TESTING_G2, TESTING_S_G2);
+        // `params fingerprint` of vk-gen-examples on the same params.
+        assert!(
+            param_store::fingerprint(signer::address_of(&signer)) == x"76d6448c14c99eacea9a580dfc94249e38f5bac664c847c64296e521c66ca73b",
+            1
+        );
+    }
+
+    #[test(s = @aptos_std)]
+    public fun check_verify_with_params_fingerprint(s: &signer) {
+        crypto_algebra::enable_cryptography_algebra_natives(s);
+        let signers = unit_test::create_signers_for_testing(1);
+        let signer = vector::pop_back(&mut signers);
+        param_store::create(&signer, TESTING_G1, TESTING_G2, TESTING_S_G2);
+        publish_vector_mul_keyed(&signer, param_store::fingerprint(signer::address_of(&signer)));
+        verifier_api::verify_proof_gwc(signer::address_of(&signer), signer::address_of(&signer), vector::singleton(vector_mul_instances()), vector_mul_proof());
+    }
+
+    #[test(s = @aptos_std)]
+    #[expected_failure(abort_code = 0x10002, location = verifier_api::verifier_api)]
+    public fun check_verify_with_other_params(s: &signer) {
+        crypto_algebra::enable_cryptography_algebra_natives(s);
+        let signers = unit_test::create_signers_for_testing(1);
+        let signer = vector::pop_back(&mut signers);
+        param_store::create(&signer, TESTING_G1, TESTING_G2, TESTING_S_G2);
+        // the vector-mul circuit of `check_verify_ok`, keyed with params of another fingerprint.
+        publish_vector_mul_keyed(&signer, x"0000000000000000000000000000000000000000000000000000000000000000");
+        // the params are checked before the proof is read.
+        verifier_api::verify_proof_gwc(signer::address_of(&signer), signer::address_of(&signer), vector[], x"");
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0x10003, location = verifier_api::verifier_api)]
+    public fun check_publish_with_short_srs_fingerprint() {
+        let signers = unit_test::create_signers_for_testing(1);
+        let signer = vector::pop_back(&mut signers);
+        publish_vector_mul_keyed(&signer, x"00");
+    }
+
+    #[test(s = @aptos_std)]
+    public fun check_verify_batch_proof_ok(s: &signer) {
+        crypto_algebra::enable_cryptography_algebra_natives(s);
+        let signers = unit_test::create_signers_for_testing(1);
+        let signer = vector::pop_back(&mut signers);
+        param_store::create(&signer, TESTING_G1, TESTING_G2, TESTING_S_G2);
+        publish_vector_mul(&signer);
+        // a batch of one circuit instance, with a single instance column.
+        verifier_api::verify_batch_proof(
+            signer::address_of(&signer),
+            signer::address_of(&signer),
+            vector::singleton(vector::singleton(vector_mul_instances())),
+            vector_mul_proof(),
+            0
+        );
+    }
+
+    #[test(s = @aptos_std)]
+    #[expected_failure]
+    public fun check_verify_batch_proof_of_other_size(s: &signer) {
+        crypto_algebra::enable_cryptography_algebra_natives(s);
+        let signers = unit_test::create_signers_for_testing(1);
+        let signer = vector::pop_back(&mut signers);
+        param_store::create(&signer, TESTING_G1, TESTING_G2, TESTING_S_G2);
+        publish_vector_mul(&signer);
+        // the proof covers one circuit instance, not two.
+        let instances = vector::singleton(vector_mul_instances());
+        verifier_api::verify_batch_proof(
+            signer::address_of(&signer),
+            signer::address_of(&signer),
+            vector[instances, instances],
+            vector_mul_proof(),
+            0
+        );
+    }
+
+    #[test]
+    public fun check_deployment_addresses() {
+        // the addresses `derive-address --deployer cafe` computes, see `test_deployment_addresses` of aptos-verifier-api.
+        assert!(object::create_object_address(&@0xcafe, b"vector_mul") == @0xc183ceffac1717fd3df0013d8c67795e70012f16ff25551798842f3a58106605, 0);
+        assert!(account::create_resource_address(&@0xcafe, b"vector_mul") == @0x6969abf356b79e60df8103deac1ce46a0dbdf01d1716f9635ef18aa9f4fdd972, 1);
+    }
+
+    #[test]
+    public fun check_mock_verify_batch_proof() {
+        verifier_api::mock_verify_batch_proof(@0x1, @0x2, vector[vector[], vector[vector[x"01"]]], x"", 0);
+    }
+
+    #[test_only]
+    fun publish_vector_mul(signer: &signer) {
+        publish_vector_mul_keyed(signer, vector[]);
+    }
+
+    #[test_only]
+    /// publish vector-mul with the fingerprint of the params it was keyed with, none if empty.
+    fun publish_vector_mul_keyed(signer: &signer, srs_fingerprint: vector<u8>) {
+        // protocol of example vector-mul
+        // generated by `cargo run --release --  --param-path params/challenge_0078-kzg_bn254_16.srs --verifier-address 0xcfae5b6bd579e7aff4274aeca434bb500c024b89c139b545c6eeb27bfafea8c1 build-publish-vk-aptos-txn --example vector-mul -o vk_deployment`
+        let general_info = vector[
+            x"3f8c4673ba5c3eb53ddb38c02cfa47483c4d6a25fbc64ea44c50309fbd413d2c",
+            x"a7c40e6e753cfd404ff8e10e1352a3eb77c8e0495bf1d9b7c67410ce4f2a5a98",
+            x"f1ad77997188215fb04be428716b389ff29c6ef944e36ab74ffaa9d192d324858d6c5ae20545006f1f4c93446419c848a95dd8e567a2990c1cba488d4a196f0fa9f81ad6c30148acd3011bfd4d6f9a129c577dd059acb81a26d86e7afab696aba66350b2d5606122fa883ea1232fc384ffae8f8e3db990ef760506abf2f5f284",
+            x"0c",
+            x"01000000",
+            x"03000000",
+            x"0100000000000000",
+            x"0100000000000000",
+            x"000000",
+            x"",
+            x"00",
+            x"00"
+        ];
+        let advice_queries = vector[
+            x"01000000000100000000",
+            x"01010000000100000000",
+            x"01020000000100000000"
+        ];
+        let instance_queries = vector[
+            x"03000000000100000000"
+        ];
+        let fixed_queries = vector[x"02000000000100000000"];
+        let permutation_columns = vector[
+            x"0300000000",
+            x"0100000000",
+            x"0101000000",
+            x"0102000000"
+        ];
+        let fields_pool = vector[];
+        let gates = vector[
+            x"080200070803000301060302"
+        ];
+        let lookups_input_exprs = vector[];
+        let lookups_table_exprs = vector[];
+        let shuffle_input_exprs = vector[];
+        let shuffle_exprs = vector[];
+        if (vector::is_empty(&srs_fingerprint)) {
+            verifier_api::publish_circuit(
+                signer, general_info, advice_queries, instance_queries, fixed_queries, permutation_columns,
+                fields_pool, gates, lookups_input_exprs, lookups_table_exprs, shuffle_input_exprs,
+                shuffle_exprs
+            );
+        } else {
+            verifier_api::publish_circuit_with_srs(
+                signer, general_info, advice_queries, instance_queries, fixed_queries, permutation_columns,
+                fields_pool, gates, lookups_input_exprs, lookups_table_exprs, shuffle_input_exprs,
+                shuffle_exprs, srs_fingerprint
+            );
+        }
+    }
+
+    #[test_only]
+    /// the gwc proof of example vector-mul with its hardcoded witness, for `publish_vector_mul`.
+    fun vector_mul_proof(): vector<u8> {
+        x"3681c1d1817d8b98dd7b522ccecf2cf190a4a8e1cf6f8a743b7acdcab33e26132211205f30ce2b988edbc8bcea6b4c5970280f1aa1b8248264ca8e265575878d00bd3fc17b08e9dd6bd7f6484ab6a79997c68d542eacd86167eb42d169f42690917e64f9a25d79b2a35c01e50fd522cf1e0f1972a268ab99fc2a66fab4c1fbaac7f928a6bbe3678f2210aca958e4274b36cbabecc23249c146db0e5c6d5f0e8e27b134238e08c4edf54b874dd1d5a0176872fd428d2e72765a86ed36f1c82600435ffc17a391a89cdc95642ce72e58ef8f6a8d13844e730c75d291e30bf4dca2137aded2207d1fc5226f90535c4758d1dc436edda9f049067bbb465c747c6001d61e52ea8e5c441ed55f4244c85b515f7fd28229641b829594e612783292440da0457df8f2ca01b837c681e9a8f34bb722082aa62b0f7a6eaaf9b874197f309707266faa9f823e2d4ded37f2a001ebbc2bc1e58a912673e088d643ee5af23a1a7aec68c383498a080b617c98af567a96510c1f5ac6988fb2cdc7a6af0ee1fd26f4e8557b441079497d91be7c59810c3148c9a479f893261e7c0c5ee008577f2718fd4aaa5ba24c4421962a05be3e0ec807f7f46aa55ca1b5bb78cf5a6bc27b0139d5e24bca1b57b1d66bd3c3ad17d6c219c85d14f8ce7311ad1f12453db020300d119d602a3bb5ed00f1b0718bbff5ff03ebe3d65f8d94200769bd1f90a4952dbd5f54d0adb5c309fe6e01fc24f9baef6c6b571c8049f234c0c139bc01a3df293805bb83f48a4c26fca011e67bf7080f01ad3151725c4a391f97a0d7b34d3517e16a4cec8a676c67a133c3d42ec2d644b05feaa6ac0ca09c44134fe77740bc19abd4a981d7fd95cebb4bee3787d9d163e57d161ded20203f5ab3f051ecfec71a4cb0716ada6eca73949c893f83a75d6a69b918604876359076cc58817baa8828a3e127b5111be026655ae8edefd79ced3e5bd05521bdbe3198404327c0408f161b6a98303867cb77b3e6ce674a457b0ecd738107db2966534797adb039b48d0d8fe6df89f67d84f42cb19b2d3799b168ef550450f878b59e4fe1bb9c8d64871e896eedc50241740c64cbab5aa2415043ef5c095529370fb03adbc6ec8e22852c7aa92729889a956df02a7fdc66db20d151805e7afb33e9d4c53ea41fa90bf822ea572c361cf6136c7d6ba50275bff40721f888216b4d19f3dd18d7768a41b2053039e17cdbd0d70721e76224f2bb5b541c03123da922b23948b98a05a92bec0eb917bf500dd0947ca27863faf9eed01a7d81852a3016bc0e1a36457ccb6632278448d6e99bd631cbd007de80ce1b693601b723a8e3b2c75f781bc2124ee7cc076e6b66d210a85d8adae856bf722facb2d811332ab1f343f8a28d94a926ca229d652dc60060c241e7548394cd4885a67f725551a85048295e012b333ab297fd007f77e22ecb19e7ef1ee6a7cbdc3e9945beb9638ef065a697c46d79c0de01a793"
+    }
+
+    #[test_only]
+    /// the single instance column of `vector_mul_proof`.
+    fun vector_mul_instances(): vector<vector<u8>> {
+        vector[
+            x"0600000000000000000000000000000000000000000000000000000000000000",
+            x"0600000000000000000000000000000000000000000000000000000000000000",
+            x"0600000000000000000000000000000000000000000000000000000000000000"
+        ]
+    }
 }
@@ -46,6 +46,25 @@ module halo2_verifier::halo2_verifier {
         verify(params, protocol, vector::singleton(instances), proof, kzg_variant)
     }
 
+    /// verify one proof of several instances of the circuit.
+    /// instances are indexed by circuit instance, column, then row.
+    public fun verify_batch(
+        params: &Params,
+        protocol: &Protocol,
+        instances: vector<vector<vector<vector<u8>>>>,
+        proof: vector<u8>,
+        kzg_variant: u8,
+    ): bool {
+        let instances = vector::map_ref(&instances, |circuit_instances| {
+            vector::map_ref<vector<vector<u8>>, vector<Element<Fr>>>(circuit_instances, |column_instances| {
+                vector::map_ref<vector<u8>, Element<Fr>>(column_instances, |instance| {
+                    option::destroy_some( bn254_utils::deserialize_fr(instance))
+                })
+            })
+        });
+        verify(params, protocol, instances, proof, kzg_variant)
+    }
+
     public fun verify_single_vm(
         params: &Params,
         protocol: &Protocol,